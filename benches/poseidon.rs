@@ -0,0 +1,77 @@
+use ark_ff::Field;
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use schnorr_spongefish::{
+    keypair::Keypair,
+    poseidon_hash::{
+        Poseidon, params::hasher::poseidon_bn254_3x3, poseidon2::Poseidon2, sponge::PoseidonSponge,
+    },
+    signature::Signature,
+    transcript::PoseidonTranscript,
+};
+
+type F = ark_bn254::Fr;
+type G = ark_bn254::G1Projective;
+
+fn bench_permute(c: &mut Criterion) {
+    let inputs: [F; 5] = std::array::from_fn(|i| F::from(i as u64));
+    c.bench_function("permute 5x5", |b| {
+        b.iter(|| Poseidon::<F>::new(black_box(inputs)).permute())
+    });
+
+    c.bench_function("permute_optimized 5x5", |b| {
+        b.iter(|| Poseidon::<F>::new(black_box(inputs)).permute_optimized())
+    });
+
+    let inputs: [F; 3] = std::array::from_fn(|i| F::from(i as u64));
+    c.bench_function("permute 3x3", |b| {
+        b.iter(|| Poseidon::<F, 3, poseidon_bn254_3x3::Params<F>>::new(black_box(inputs)).permute())
+    });
+
+    c.bench_function("permute_optimized 3x3", |b| {
+        b.iter(|| {
+            Poseidon::<F, 3, poseidon_bn254_3x3::Params<F>>::new(black_box(inputs))
+                .permute_optimized()
+        })
+    });
+
+    c.bench_function("poseidon2 permute 3x3", |b| {
+        b.iter(|| Poseidon2::<F>::new(black_box(inputs)).permute())
+    });
+}
+
+fn bench_squeeze(c: &mut Criterion) {
+    let inputs: Vec<F> = (0..10).map(F::from).collect();
+    c.bench_function("sponge squeeze 10 elements", |b| {
+        b.iter(|| {
+            let mut sponge = PoseidonSponge::<F>::new();
+            sponge.update(black_box(&inputs));
+            sponge.squeeze()
+        })
+    });
+}
+
+fn bench_signature(c: &mut Criterion) {
+    let keypair = Keypair::<F, G>::generate();
+    let message = F::ONE;
+
+    c.bench_function("Signature::sign", |b| {
+        b.iter(|| {
+            let mut transcript = PoseidonTranscript::new();
+            Signature::sign(&keypair, &mut transcript, black_box(message)).unwrap()
+        })
+    });
+
+    let mut transcript = PoseidonTranscript::new();
+    let signature = Signature::sign(&keypair, &mut transcript, message).unwrap();
+    c.bench_function("Signature::verify", |b| {
+        b.iter(|| {
+            let mut transcript = PoseidonTranscript::new();
+            signature
+                .verify(keypair.public_key, &mut transcript, black_box(message))
+                .unwrap()
+        })
+    });
+}
+
+criterion_group!(benches, bench_permute, bench_squeeze, bench_signature);
+criterion_main!(benches);
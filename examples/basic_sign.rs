@@ -3,12 +3,14 @@
 //! This example demonstrates signing and verifying a field element
 //! using a Poseidon transcript over the BN254 curve.
 
-use schnorr_spongefish::{keypair::Keypair, signature::Signature, transcript::PoseidonTranscript};
+use schnorr_spongefish::{
+    error::SchnorrError, keypair::Keypair, signature::Signature, transcript::PoseidonTranscript,
+};
 
 type F = ark_bn254::Fr;
 type G = ark_bn254::G1Projective;
 
-fn main() {
+fn main() -> Result<(), SchnorrError> {
     // Generate a keypair
     let keypair = Keypair::<F, G>::generate();
 
@@ -18,7 +20,7 @@ fn main() {
 
     // Initialize a Poseidon-based transcript for signing
     let mut transcript = PoseidonTranscript::<F>::new();
-    let signature = Signature::sign(&keypair, &mut transcript, message);
+    let signature = Signature::sign(&keypair, &mut transcript, message)?;
 
     // Initialize a fresh transcript for verification
     let mut verify_transcript = PoseidonTranscript::<F>::new();
@@ -26,5 +28,7 @@ fn main() {
 
     // Output the result
     println!("Signed message: {}", message_value);
-    println!("Signature verification passed: {}", is_valid);
+    println!("Signature verification passed: {}", is_valid.is_ok());
+
+    Ok(())
 }
@@ -3,9 +3,11 @@
 #![allow(non_snake_case)]
 
 use ark_bn254::{Fr, G1Projective};
-use schnorr_spongefish::{keypair::Keypair, musig::MuSig, transcript::PoseidonTranscript};
+use schnorr_spongefish::{
+    error::SchnorrError, keypair::Keypair, musig::MuSig, transcript::PoseidonTranscript,
+};
 
-fn main() {
+fn main() -> Result<(), SchnorrError> {
     // Define message to sign
     let message = Fr::from(16);
 
@@ -18,15 +20,15 @@ fn main() {
 
     // Aggregate public keys
     let pub_keys = [k1.public_key, k2.public_key];
-    let keyset_challenge = MuSig::keyset_challenge(&pub_keys);
-    let agg_pub_key = MuSig::agg_pub_keys(&pub_keys, keyset_challenge);
+    let keyset_challenge = MuSig::keyset_challenge(&pub_keys)?;
+    let agg_pub_key = MuSig::agg_pub_keys(&pub_keys, keyset_challenge)?;
 
     // Aggregate nonces
     let agg_R = R1 + R2;
 
     // Each participant computes their partial signature
-    let s1 = MuSig::sign(k1, message, keyset_challenge, agg_pub_key, agg_R, r1);
-    let s2 = MuSig::sign(k2, message, keyset_challenge, agg_pub_key, agg_R, r2);
+    let s1 = MuSig::sign(k1, message, keyset_challenge, agg_pub_key, agg_R, r1)?;
+    let s2 = MuSig::sign(k2, message, keyset_challenge, agg_pub_key, agg_R, r2)?;
 
     // Aggregate partial signatures
     let agg_s = s1 + s2;
@@ -36,14 +38,9 @@ fn main() {
 
     // Verify the MuSig signature
     let mut verify_transcript = PoseidonTranscript::new();
-    let is_valid = MuSig::verify(
-        &musig,
-        message,
-        &mut verify_transcript,
-        agg_pub_key,
-        agg_R,
-        agg_s,
-    );
-
-    println!("MuSig signature is valid: {}", is_valid);
+    let is_valid = musig.verify(agg_pub_key, &mut verify_transcript, message);
+
+    println!("MuSig signature is valid: {}", is_valid.is_ok());
+
+    Ok(())
 }
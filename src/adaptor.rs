@@ -0,0 +1,212 @@
+//! Schnorr adaptor signatures over the Poseidon Fiat–Shamir transcript.
+//!
+//! Inspired by:
+//! - Poelstra, "Scriptless Scripts" (2017)
+//! - Aumayr et al., "Generalized Channels from Limited Blockchain Scripts and
+//!   Adaptor Signatures": https://eprint.iacr.org/2020/476.pdf
+//!
+//! A pre-signature `(R, s')` for an adaptor point `T = t·G` satisfies
+//! `s'·G == R + c·P` with `c = H(R + T, P, message)`. Anyone knowing `t`
+//! completes it into the plain `Signature { R: R + T, s: s' + t }`, and anyone
+//! holding both the pre-signature and the completed signature extracts
+//! `t = s - s'`.
+
+#![allow(non_snake_case)]
+
+use ark_ec::CurveGroup;
+use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use rand::rngs::OsRng;
+
+use crate::{
+    encoding,
+    error::SchnorrError,
+    keypair::Keypair,
+    signature::Signature,
+    transcript::{Message, Transcript},
+};
+
+/// A pre-signature that becomes a `Signature` once the adaptor secret is added.
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PreSignature<F: Field, G: CurveGroup> {
+    pub R: G,
+    pub T: G,
+    pub s: F,
+}
+
+impl<F: Field, G: CurveGroup<ScalarField = F>> PreSignature<F, G> {
+    /// Encodes the pre-signature as compressed `R || T || s`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encoding::to_bytes(self)
+    }
+
+    /// Decodes a pre-signature produced by `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SchnorrError> {
+        Ok(encoding::from_bytes(bytes)?)
+    }
+
+    /// Pre-signs `message` for the adaptor point `T` with a fresh random nonce.
+    pub fn sign(
+        keypair: &Keypair<F, G>,
+        transcript: &mut impl Transcript<F, G>,
+        adaptor_point: G,
+        message: Message<'_, F>,
+    ) -> Result<Self, SchnorrError> {
+        if adaptor_point.is_zero() {
+            return Err(SchnorrError::IdentityPoint);
+        }
+
+        let mut rng = OsRng;
+        let r = F::rand(&mut rng);
+        let R = G::generator() * r;
+        let challenge =
+            Signature::challenge(transcript, R + adaptor_point, keypair.public_key, message)?;
+
+        Ok(PreSignature {
+            R,
+            T: adaptor_point,
+            s: r + challenge * keypair.private_key,
+        })
+    }
+
+    /// Returns the nonce `R + T` of the completed signature.
+    pub fn final_nonce(&self) -> G {
+        self.R + self.T
+    }
+
+    /// Checks that the pre-signature completes to a valid signature on
+    /// `message` once the discrete log of `T` is added.
+    pub fn verify(
+        &self,
+        public_key: G,
+        transcript: &mut impl Transcript<F, G>,
+        message: Message<'_, F>,
+    ) -> Result<(), SchnorrError> {
+        if public_key.is_zero() {
+            return Err(SchnorrError::IdentityPublicKey);
+        }
+        if self.T.is_zero() {
+            return Err(SchnorrError::IdentityPoint);
+        }
+        if self.R.is_zero() || self.final_nonce().is_zero() {
+            return Err(SchnorrError::MalformedR);
+        }
+
+        let challenge = Signature::challenge(transcript, self.final_nonce(), public_key, message)?;
+        if G::generator() * self.s != self.R + public_key * challenge {
+            return Err(SchnorrError::EquationMismatch);
+        }
+        Ok(())
+    }
+
+    /// Completes the pre-signature with the adaptor secret `t`.
+    ///
+    /// Fails with `InvalidAdaptorSecret` unless `t·G == T`.
+    pub fn complete(&self, adaptor_secret: F) -> Result<Signature<F, G>, SchnorrError> {
+        if G::generator() * adaptor_secret != self.T {
+            return Err(SchnorrError::InvalidAdaptorSecret);
+        }
+
+        Ok(Signature {
+            R: self.final_nonce(),
+            s: self.s + adaptor_secret,
+        })
+    }
+
+    /// Recovers the adaptor secret `t` from the completed `signature`.
+    ///
+    /// Fails with `InvalidAdaptorSecret` if `signature` was not completed
+    /// from this pre-signature.
+    pub fn extract(&self, signature: &Signature<F, G>) -> Result<F, SchnorrError> {
+        let adaptor_secret = signature.s - self.s;
+        if signature.R != self.final_nonce() || G::generator() * adaptor_secret != self.T {
+            return Err(SchnorrError::InvalidAdaptorSecret);
+        }
+
+        Ok(adaptor_secret)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::transcript::PoseidonTranscript;
+
+    type F = ark_bn254::Fr;
+    type G = ark_bn254::G1Projective;
+
+    #[test]
+    fn test_adaptor_complete_and_extract() {
+        let keypair = Keypair::<F, G>::generate();
+        let adaptor = Keypair::<F, G>::generate();
+        let message = Message::Bytes(b"swap 5 for 7");
+
+        let mut transcript = PoseidonTranscript::new();
+        let pre_signature =
+            PreSignature::sign(&keypair, &mut transcript, adaptor.public_key, message).unwrap();
+        let mut transcript = PoseidonTranscript::new();
+        assert!(
+            pre_signature
+                .verify(keypair.public_key, &mut transcript, message)
+                .is_ok()
+        );
+
+        // The pre-signature alone is not a valid signature.
+        let incomplete = Signature {
+            R: pre_signature.final_nonce(),
+            s: pre_signature.s,
+        };
+        let mut transcript = PoseidonTranscript::new();
+        assert_eq!(
+            incomplete.verify_bytes(keypair.public_key, &mut transcript, b"swap 5 for 7"),
+            Err(SchnorrError::EquationMismatch)
+        );
+
+        let signature = pre_signature.complete(adaptor.private_key).unwrap();
+        let mut transcript = PoseidonTranscript::new();
+        assert!(
+            signature
+                .verify_bytes(keypair.public_key, &mut transcript, b"swap 5 for 7")
+                .is_ok()
+        );
+        assert_eq!(
+            pre_signature.extract(&signature).unwrap(),
+            adaptor.private_key
+        );
+    }
+
+    #[test]
+    fn test_adaptor_rejects_wrong_inputs() {
+        let keypair = Keypair::<F, G>::generate();
+        let adaptor = Keypair::<F, G>::generate();
+        let message = Message::Scalar(F::from(16));
+
+        let mut transcript = PoseidonTranscript::new();
+        let pre_signature =
+            PreSignature::sign(&keypair, &mut transcript, adaptor.public_key, message).unwrap();
+
+        let mut transcript = PoseidonTranscript::new();
+        assert_eq!(
+            pre_signature.verify(
+                keypair.public_key,
+                &mut transcript,
+                Message::Scalar(F::from(17))
+            ),
+            Err(SchnorrError::EquationMismatch)
+        );
+        assert_eq!(
+            pre_signature.complete(F::from(1)).err(),
+            Some(SchnorrError::InvalidAdaptorSecret)
+        );
+
+        let mut transcript = PoseidonTranscript::new();
+        let unrelated = Signature::sign(&keypair, &mut transcript, F::from(16)).unwrap();
+        assert_eq!(
+            pre_signature.extract(&unrelated),
+            Err(SchnorrError::InvalidAdaptorSecret)
+        );
+
+        let bytes = pre_signature.to_bytes();
+        assert_eq!(PreSignature::from_bytes(&bytes).unwrap(), pre_signature);
+    }
+}
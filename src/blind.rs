@@ -0,0 +1,251 @@
+//! Blind Schnorr signatures in the "Clause" variant.
+//!
+//! Inspired by:
+//! - Fuchsbauer, Plouviez, Seurin, "Blind Schnorr Signatures and Signed
+//!   ElGamal Encryption in the Algebraic Group Model": https://eprint.iacr.org/2019/877.pdf
+//! - Benhamouda et al., "On the (in)security of ROS": https://eprint.iacr.org/2020/945.pdf
+//!
+//! Plain blind Schnorr, where the signer sends one `R = r·G` and answers one
+//! blinded challenge, is broken by the ROS attack once the signer runs about
+//! `log p` sessions concurrently. This module implements Clause blind Schnorr
+//! instead: the signer commits to two nonces, the user blinds both, and the
+//! signer answers only one of the two challenges, picked at random. Forging
+//! then requires solving the modified ROS problem, for which no polynomial
+//! attack is known, while the final signature is an ordinary `Signature`.
+//!
+//! Protocol, for signer key `P = x·G`:
+//! 1. Signer: `BlindSigner::commit` samples `r_0, r_1` and sends `R_0, R_1`.
+//! 2. User: `BlindingSession::new` samples `α_b, β_b`, computes
+//!    `R'_b = R_b + α_b·G + β_b·P` and `c'_b = H(R'_b, P, m)` on a fresh
+//!    Poseidon transcript, and sends `c_b = c'_b + β_b` for both `b`.
+//! 3. Signer: `BlindSigner::sign` picks a random `b` and returns `s = r_b + c_b·x`.
+//! 4. User: `BlindingSession::unblind` checks `s·G == R_b + c_b·P` and outputs
+//!    `(R'_b, s + α_b)`, which verifies under `P` with `Signature::verify`.
+//!
+//! The signer sees neither `R'_b` nor `c'_b`, so it cannot link the final
+//! signature to the session that produced it.
+
+#![allow(non_snake_case)]
+
+use ark_ec::CurveGroup;
+use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use rand::{Rng, rngs::OsRng};
+use std::fmt;
+
+use crate::{
+    error::SchnorrError,
+    keypair::Keypair,
+    signature::Signature,
+    transcript::{Message, PoseidonTranscript},
+};
+
+/// The signer's two secret nonces for one session.
+///
+/// Like the MuSig2 `SecNonce`, it is neither `Clone` nor serializable and is
+/// consumed by `BlindSigner::sign`. Its `Debug` output omits both nonces.
+pub struct SignerNonces<F: Field> {
+    r: [F; 2],
+}
+
+impl<F: Field> fmt::Debug for SignerNonces<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SignerNonces").finish_non_exhaustive()
+    }
+}
+
+/// The signer's first message: commitments to both nonces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct SignerCommitment<G: CurveGroup> {
+    pub R: [G; 2],
+}
+
+/// The user's blinded challenges, one per clause.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct BlindRequest<F: Field> {
+    pub c: [F; 2],
+}
+
+/// The signer's answer to the clause it picked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct BlindResponse<F: Field> {
+    pub clause: u8,
+    pub s: F,
+}
+
+/// Signer side of the protocol.
+#[derive(Debug)]
+pub struct BlindSigner;
+
+impl BlindSigner {
+    /// Samples both nonces; send the returned commitment to the user.
+    pub fn commit<F: Field, G: CurveGroup<ScalarField = F>>()
+    -> (SignerNonces<F>, SignerCommitment<G>) {
+        let mut rng = OsRng;
+        let r = [F::rand(&mut rng), F::rand(&mut rng)];
+        let commitment = SignerCommitment {
+            R: r.map(|r| G::generator() * r),
+        };
+
+        (SignerNonces { r }, commitment)
+    }
+
+    /// Answers one randomly chosen blinded challenge, consuming the nonces.
+    pub fn sign<F: Field, G: CurveGroup<ScalarField = F>>(
+        keypair: &Keypair<F, G>,
+        nonces: SignerNonces<F>,
+        request: &BlindRequest<F>,
+    ) -> BlindResponse<F> {
+        let mut rng = OsRng;
+        let clause = rng.gen_range(0..2);
+
+        BlindResponse {
+            clause: clause as u8,
+            s: nonces.r[clause] + request.c[clause] * keypair.private_key,
+        }
+    }
+}
+
+/// User side of the protocol, holding the blinding factors for both clauses.
+///
+/// Its `Debug` output shows only what the signer sees, omitting the blinding
+/// factors and blinded nonces that would link the signature to the session.
+pub struct BlindingSession<F: Field, G: CurveGroup> {
+    public_key: G,
+    commitment: SignerCommitment<G>,
+    alpha: [F; 2],
+    blinded_R: [G; 2],
+    request: BlindRequest<F>,
+}
+
+impl<F: Field, G: CurveGroup> fmt::Debug for BlindingSession<F, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BlindingSession")
+            .field("public_key", &self.public_key)
+            .field("commitment", &self.commitment)
+            .field("request", &self.request)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F: Field, G: CurveGroup<ScalarField = F>> BlindingSession<F, G> {
+    /// Blinds the signer's commitments for `message`; send `request()` to the signer.
+    pub fn new(
+        public_key: G,
+        commitment: SignerCommitment<G>,
+        message: Message<'_, F>,
+    ) -> Result<Self, SchnorrError> {
+        if public_key.is_zero() {
+            return Err(SchnorrError::IdentityPublicKey);
+        }
+        if commitment.R.iter().any(|R| R.is_zero()) {
+            return Err(SchnorrError::MalformedR);
+        }
+
+        let mut rng = OsRng;
+        let alpha = [F::rand(&mut rng), F::rand(&mut rng)];
+        let beta = [F::rand(&mut rng), F::rand(&mut rng)];
+        let mut blinded_R = [G::zero(); 2];
+        let mut c = [F::ZERO; 2];
+        for clause in 0..2 {
+            blinded_R[clause] =
+                commitment.R[clause] + G::generator() * alpha[clause] + public_key * beta[clause];
+            let mut transcript = PoseidonTranscript::new();
+            c[clause] =
+                Signature::challenge(&mut transcript, blinded_R[clause], public_key, message)?
+                    + beta[clause];
+        }
+
+        Ok(BlindingSession {
+            public_key,
+            commitment,
+            alpha,
+            blinded_R,
+            request: BlindRequest { c },
+        })
+    }
+
+    /// Returns the blinded challenges to send to the signer.
+    pub fn request(&self) -> BlindRequest<F> {
+        self.request
+    }
+
+    /// Checks the signer's answer and unblinds it into a plain signature.
+    ///
+    /// Fails with `InvalidEncoding` for a clause other than 0 or 1 and with
+    /// `EquationMismatch` if the answer is not valid for the blinded challenge.
+    pub fn unblind(self, response: &BlindResponse<F>) -> Result<Signature<F, G>, SchnorrError> {
+        let clause = response.clause as usize;
+        let R = *self
+            .commitment
+            .R
+            .get(clause)
+            .ok_or(SchnorrError::InvalidEncoding)?;
+        if G::generator() * response.s != R + self.public_key * self.request.c[clause] {
+            return Err(SchnorrError::EquationMismatch);
+        }
+
+        Ok(Signature {
+            R: self.blinded_R[clause],
+            s: response.s + self.alpha[clause],
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    type F = ark_bn254::Fr;
+    type G = ark_bn254::G1Projective;
+
+    #[test]
+    fn test_blind_signature_verifies() {
+        let keypair = Keypair::<F, G>::generate();
+        let message = F::from(16);
+
+        // Repeat so that both clauses are almost surely exercised.
+        for _ in 0..16 {
+            let (nonces, commitment) = BlindSigner::commit::<F, G>();
+            let session =
+                BlindingSession::new(keypair.public_key, commitment, Message::Scalar(message))
+                    .unwrap();
+            let request = session.request();
+            let response = BlindSigner::sign(&keypair, nonces, &request);
+            let signature = session.unblind(&response).unwrap();
+
+            // The signer never sees the final nonce.
+            assert!(!commitment.R.contains(&signature.R));
+            let mut transcript = PoseidonTranscript::new();
+            assert!(
+                signature
+                    .verify(keypair.public_key, &mut transcript, message)
+                    .is_ok()
+            );
+            let mut transcript = PoseidonTranscript::new();
+            assert_eq!(
+                signature.verify(keypair.public_key, &mut transcript, F::from(17)),
+                Err(SchnorrError::EquationMismatch)
+            );
+        }
+    }
+
+    #[test]
+    fn test_blind_signature_rejects_bad_response() {
+        let keypair = Keypair::<F, G>::generate();
+        let (nonces, commitment) = BlindSigner::commit::<F, G>();
+        let session = BlindingSession::new(
+            keypair.public_key,
+            commitment,
+            Message::Bytes(b"credential"),
+        )
+        .unwrap();
+        let mut response = BlindSigner::sign(&keypair, nonces, &session.request());
+
+        response.s += F::from(1);
+        assert_eq!(
+            session.unblind(&response).err(),
+            Some(SchnorrError::EquationMismatch)
+        );
+    }
+}
@@ -0,0 +1,456 @@
+//! Pedersen distributed key generation with Feldman verifiable secret sharing.
+//!
+//! Inspired by:
+//! - Pedersen, "A Threshold Cryptosystem without a Trusted Party" (Eurocrypt '91)
+//! - The FROST key generation with proofs of knowledge: https://eprint.iacr.org/2020/852.pdf
+//!
+//! Every participant acts as a dealer of a random secret:
+//! 1. `Dealer::new` samples a polynomial of degree `threshold - 1` and
+//!    broadcasts a `Round1Package` with its Feldman commitments and a Schnorr
+//!    proof of knowledge of its secret.
+//! 2. Each dealer privately sends `Dealer::share_for(j)` to participant `j`,
+//!    who checks it with `verify_share` and broadcasts a `Complaint` if it fails.
+//! 3. The accused dealer answers a complaint by publishing the disputed share;
+//!    `resolve_complaint` tells everyone whether the dealer is disqualified.
+//! 4. `finalize` checks the packages of all qualified dealers again and sums
+//!    their shares into a FROST `KeyShare`. The group key is the sum of their constant-term commitments,
+//!    and no single party ever knows the group secret.
+
+use ark_ec::CurveGroup;
+use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use rand::rngs::OsRng;
+use std::fmt;
+
+use crate::{
+    error::SchnorrError,
+    frost::KeyShare,
+    keypair::Keypair,
+    shamir::{self, Polynomial, SecretShare},
+    signature::Signature,
+    transcript::PoseidonTranscript,
+};
+
+/// Domain separator signed in the proof of knowledge ("dkg" in ASCII).
+const DKG_TAG: u64 = 0x646b67;
+
+/// A dealer's broadcast: Feldman commitments and a proof of knowledge of `a_0`.
+#[derive(Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Round1Package<F: Field, G: CurveGroup> {
+    pub index: u32,
+    pub commitments: Vec<G>,
+    pub proof: Signature<F, G>,
+}
+
+/// A share `f_sender(receiver)` sent privately from one dealer to one participant.
+///
+/// Its `Debug` output omits the share value.
+#[derive(Clone, Copy, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct DealtShare<F: Field> {
+    pub sender: u32,
+    pub receiver: u32,
+    pub value: F,
+}
+
+/// A broadcast accusation that `accused` sent `accuser` a share that fails verification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Complaint {
+    pub accuser: u32,
+    pub accused: u32,
+}
+
+/// One participant's secret sharing polynomial for a DKG run.
+///
+/// Its `Debug` output omits the polynomial.
+pub struct Dealer<F: Field, G: CurveGroup> {
+    index: u32,
+    participants: u32,
+    polynomial: Polynomial<F>,
+    package: Round1Package<F, G>,
+}
+
+impl<F: Field> fmt::Debug for DealtShare<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DealtShare")
+            .field("sender", &self.sender)
+            .field("receiver", &self.receiver)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F: Field, G: CurveGroup> fmt::Debug for Dealer<F, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Dealer")
+            .field("index", &self.index)
+            .field("participants", &self.participants)
+            .field("package", &self.package)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F: Field, G: CurveGroup<ScalarField = F>> Dealer<F, G> {
+    /// Samples the dealer's polynomial and builds its round-one broadcast.
+    pub fn new(index: u32, threshold: u32, participants: u32) -> Result<Self, SchnorrError> {
+        shamir::check_threshold(threshold, participants)?;
+        if index == 0 || index > participants {
+            return Err(SchnorrError::InvalidParticipantIndex(index));
+        }
+
+        let polynomial = Polynomial::random(F::rand(&mut OsRng), threshold as usize - 1);
+        let commitments = polynomial.commitments();
+        let secret = Keypair::from_private_key(polynomial.coefficients()[0]);
+        let mut transcript = PoseidonTranscript::new();
+        let proof = Signature::sign_scalars(&secret, &mut transcript, &proof_message(index))?;
+
+        Ok(Dealer {
+            index,
+            participants,
+            polynomial,
+            package: Round1Package {
+                index,
+                commitments,
+                proof,
+            },
+        })
+    }
+
+    /// Returns the package to broadcast in round one.
+    pub fn package(&self) -> &Round1Package<F, G> {
+        &self.package
+    }
+
+    /// Returns the share to send privately to participant `receiver`.
+    pub fn share_for(&self, receiver: u32) -> Result<DealtShare<F>, SchnorrError> {
+        if receiver == 0 || receiver > self.participants {
+            return Err(SchnorrError::InvalidParticipantIndex(receiver));
+        }
+
+        Ok(DealtShare {
+            sender: self.index,
+            receiver,
+            value: self.polynomial.evaluate(F::from(receiver)),
+        })
+    }
+}
+
+impl<F: Field, G: CurveGroup<ScalarField = F>> Round1Package<F, G> {
+    /// Checks the commitment count and the proof of knowledge of the dealer's secret.
+    ///
+    /// Fails with `InvalidThreshold` for a zero threshold, with
+    /// `LengthMismatch` for the wrong number of commitments and with
+    /// `InvalidProofOfKnowledge` naming the dealer if the proof does not verify.
+    pub fn verify(&self, threshold: u32) -> Result<(), SchnorrError> {
+        if threshold == 0 {
+            return Err(SchnorrError::InvalidThreshold {
+                threshold,
+                participants: 0,
+            });
+        }
+        if self.commitments.len() != threshold as usize {
+            return Err(SchnorrError::LengthMismatch {
+                expected: threshold as usize,
+                found: self.commitments.len(),
+            });
+        }
+
+        let mut transcript = PoseidonTranscript::new();
+        self.proof
+            .verify_scalars(
+                self.commitments[0],
+                &mut transcript,
+                &proof_message(self.index),
+            )
+            .map_err(|_| SchnorrError::InvalidProofOfKnowledge(self.index))
+    }
+}
+
+/// The message signed in a dealer's proof of knowledge, binding it to its index.
+fn proof_message<F: Field>(index: u32) -> [F; 2] {
+    [F::from(DKG_TAG), F::from(index)]
+}
+
+/// Checks a received share against the sender's Feldman commitments.
+///
+/// Fails with `InvalidShare` naming the sender if `share` does not match the
+/// commitments in `package`; the receiver should then broadcast a `Complaint`.
+pub fn verify_share<F: Field, G: CurveGroup<ScalarField = F>>(
+    share: &DealtShare<F>,
+    package: &Round1Package<F, G>,
+) -> Result<(), SchnorrError> {
+    let secret_share = SecretShare {
+        index: share.receiver,
+        value: share.value,
+    };
+    if share.sender != package.index || !shamir::verify_share(&secret_share, &package.commitments) {
+        return Err(SchnorrError::InvalidShare(package.index));
+    }
+    Ok(())
+}
+
+/// Decides a complaint from the share the accused dealer published in response.
+///
+/// Returns `Ok(())` if the published share is for the accuser and matches the
+/// dealer's commitments: the complaint is unfounded and the accuser uses the
+/// published share. Otherwise fails with `InvalidShare` and every participant
+/// disqualifies the dealer. A dealer that does not answer is disqualified too.
+pub fn resolve_complaint<F: Field, G: CurveGroup<ScalarField = F>>(
+    complaint: &Complaint,
+    package: &Round1Package<F, G>,
+    published: &DealtShare<F>,
+) -> Result<(), SchnorrError> {
+    if package.index != complaint.accused
+        || published.sender != complaint.accused
+        || published.receiver != complaint.accuser
+    {
+        return Err(SchnorrError::InvalidShare(complaint.accused));
+    }
+
+    verify_share(published, package)
+}
+
+/// Combines the shares of all qualified dealers into participant `index`'s key share.
+///
+/// `packages` are the round-one broadcasts of the qualified dealers and
+/// `shares` the shares this participant received from them. Fails with
+/// `InvalidThreshold` if `threshold` is zero or exceeds the number of
+/// dealers, with `InvalidParticipantIndex` for a repeated dealer, and with
+/// the error of `Round1Package::verify` for a malformed package. Every share
+/// is checked again, and a missing or invalid one fails with `InvalidShare`.
+pub fn finalize<F: Field, G: CurveGroup<ScalarField = F>>(
+    index: u32,
+    threshold: u32,
+    packages: &[Round1Package<F, G>],
+    shares: &[DealtShare<F>],
+) -> Result<KeyShare<F, G>, SchnorrError> {
+    shamir::check_threshold(threshold, packages.len() as u32)?;
+    for (position, package) in packages.iter().enumerate() {
+        if packages[..position]
+            .iter()
+            .any(|other| other.index == package.index)
+        {
+            return Err(SchnorrError::InvalidParticipantIndex(package.index));
+        }
+        package.verify(threshold)?;
+    }
+
+    let mut secret_share = F::ZERO;
+    for package in packages {
+        let share = shares
+            .iter()
+            .find(|share| share.sender == package.index && share.receiver == index)
+            .ok_or(SchnorrError::InvalidShare(package.index))?;
+        verify_share(share, package)?;
+        secret_share += share.value;
+    }
+
+    Ok(KeyShare {
+        index,
+        keypair: Keypair::from_private_key(secret_share),
+        group_public_key: group_public_key(packages),
+    })
+}
+
+/// Returns the group public key `Σ C_{i,0}` of the qualified dealers.
+///
+/// `packages` must have passed `Round1Package::verify`.
+pub fn group_public_key<F: Field, G: CurveGroup<ScalarField = F>>(
+    packages: &[Round1Package<F, G>],
+) -> G {
+    packages.iter().map(|package| package.commitments[0]).sum()
+}
+
+/// Computes participant `index`'s public verification share from the broadcasts alone.
+pub fn verification_share<F: Field, G: CurveGroup<ScalarField = F>>(
+    index: u32,
+    packages: &[Round1Package<F, G>],
+) -> G {
+    packages
+        .iter()
+        .map(|package| shamir::evaluate_commitments(&package.commitments, index))
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        frost::{Session, commit},
+        transcript::Message,
+    };
+    use ark_ec::PrimeGroup;
+
+    type F = ark_bn254::Fr;
+    type G = ark_bn254::G1Projective;
+
+    /// Runs round one for `participants` dealers and checks every package.
+    fn deal(threshold: u32, participants: u32) -> Vec<Dealer<F, G>> {
+        let dealers: Vec<_> = (1..=participants)
+            .map(|index| Dealer::<F, G>::new(index, threshold, participants).unwrap())
+            .collect();
+        for dealer in &dealers {
+            dealer.package().verify(threshold).unwrap();
+        }
+        dealers
+    }
+
+    /// Runs round two honestly and finalizes every participant's key share.
+    fn key_shares(dealers: &[Dealer<F, G>], threshold: u32) -> Vec<KeyShare<F, G>> {
+        let packages: Vec<_> = dealers.iter().map(|d| d.package().clone()).collect();
+        (1..=dealers.len() as u32)
+            .map(|index| {
+                let shares: Vec<_> = dealers
+                    .iter()
+                    .map(|dealer| dealer.share_for(index).unwrap())
+                    .collect();
+                finalize(index, threshold, &packages, &shares).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_dkg_output_signs_with_frost() {
+        let dealers = deal(3, 5);
+        let shares = key_shares(&dealers, 3);
+        let packages: Vec<_> = dealers.iter().map(|d| d.package().clone()).collect();
+        let group_public_key = group_public_key(&packages);
+
+        for share in &shares {
+            assert_eq!(share.group_public_key, group_public_key);
+            assert_eq!(
+                share.keypair.public_key,
+                verification_share(share.index, &packages)
+            );
+        }
+
+        let signers = [&shares[0], &shares[2], &shares[4]];
+        let (nonces, commitments): (Vec<_>, Vec<_>) =
+            signers.iter().map(|s| commit::<F, G>(s.index)).unzip();
+        let message = F::from(16);
+        let session =
+            Session::new(group_public_key, &commitments, Message::Scalar(message)).unwrap();
+        let signature_shares: Vec<_> = signers
+            .iter()
+            .zip(nonces)
+            .map(|(share, nonces)| session.sign(share, nonces).unwrap())
+            .collect();
+        let verification_shares: Vec<_> = signers.iter().map(|s| s.keypair.public_key).collect();
+        let signature = session
+            .aggregate(&signature_shares, &verification_shares)
+            .unwrap();
+
+        let mut transcript = PoseidonTranscript::new();
+        assert!(
+            signature
+                .verify(group_public_key, &mut transcript, message)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_dkg_rejects_bad_proof_of_knowledge() {
+        let dealers = deal(2, 3);
+        let mut package = dealers[1].package().clone();
+        package.commitments[0] = dealers[0].package().commitments[0];
+
+        assert_eq!(
+            package.verify(2),
+            Err(SchnorrError::InvalidProofOfKnowledge(2))
+        );
+    }
+
+    #[test]
+    fn test_dkg_finalize_rejects_malformed_packages() {
+        let dealers = deal(2, 3);
+        let packages: Vec<_> = dealers.iter().map(|d| d.package().clone()).collect();
+        let shares: Vec<_> = dealers.iter().map(|d| d.share_for(1).unwrap()).collect();
+
+        let mut empty = packages.clone();
+        empty[1].commitments.clear();
+        assert_eq!(
+            finalize(1, 2, &empty, &shares).err(),
+            Some(SchnorrError::LengthMismatch {
+                expected: 2,
+                found: 0
+            })
+        );
+
+        let mut short = packages.clone();
+        short[2].commitments.pop();
+        assert_eq!(
+            finalize(1, 2, &short, &shares).err(),
+            Some(SchnorrError::LengthMismatch {
+                expected: 2,
+                found: 1
+            })
+        );
+
+        let repeated = [
+            packages[0].clone(),
+            packages[1].clone(),
+            packages[0].clone(),
+        ];
+        assert_eq!(
+            finalize(1, 2, &repeated, &shares).err(),
+            Some(SchnorrError::InvalidParticipantIndex(1))
+        );
+        assert_eq!(
+            finalize(1, 0, &packages, &shares).err(),
+            Some(SchnorrError::InvalidThreshold {
+                threshold: 0,
+                participants: 3
+            })
+        );
+        assert_eq!(
+            finalize::<F, G>(1, 2, &[], &[]).err(),
+            Some(SchnorrError::InvalidThreshold {
+                threshold: 2,
+                participants: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_dkg_complaint_resolution() {
+        let dealers = deal(2, 3);
+        let package = dealers[0].package();
+
+        // Dealer 1 sends participant 3 a corrupted share.
+        let mut bad_share = dealers[0].share_for(3).unwrap();
+        bad_share.value += F::from(1);
+        assert_eq!(
+            verify_share(&bad_share, package),
+            Err(SchnorrError::InvalidShare(1))
+        );
+        let complaint = Complaint {
+            accuser: 3,
+            accused: 1,
+        };
+
+        // Publishing the correct share clears the dealer; repeating the bad one does not.
+        let published = dealers[0].share_for(3).unwrap();
+        assert_eq!(resolve_complaint(&complaint, package, &published), Ok(()));
+        assert_eq!(
+            resolve_complaint(&complaint, package, &bad_share),
+            Err(SchnorrError::InvalidShare(1))
+        );
+        assert_eq!(
+            resolve_complaint(&complaint, package, &dealers[0].share_for(2).unwrap()),
+            Err(SchnorrError::InvalidShare(1))
+        );
+
+        // Without dealer 1, the remaining dealers still agree on a key.
+        let shares = key_shares(&dealers[1..], 2);
+        let packages: Vec<_> = dealers[1..].iter().map(|d| d.package().clone()).collect();
+        let secret = shamir::interpolate(
+            &shares
+                .iter()
+                .map(|share| SecretShare {
+                    index: share.index,
+                    value: share.keypair.private_key,
+                })
+                .collect::<Vec<_>>(),
+        )
+        .unwrap();
+        assert_eq!(G::generator() * secret, group_public_key(&packages));
+    }
+}
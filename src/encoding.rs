@@ -0,0 +1,27 @@
+//! Fixed-length byte encodings shared by signatures and keys.
+//!
+//! Every type is encoded as the concatenation of the compressed canonical
+//! serialization of its fields, so the length only depends on the curve.
+//! Decoding runs the full arkworks validation (points on the curve and in the
+//! prime-order subgroup, scalars below the modulus) and rejects trailing bytes.
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+
+/// Encodes a value as its compressed canonical serialization.
+pub(crate) fn to_bytes<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(value.compressed_size());
+    value
+        .serialize_compressed(&mut bytes)
+        .expect("serializing into a Vec cannot fail");
+    bytes
+}
+
+/// Decodes and validates a value, requiring the input to be consumed exactly.
+pub(crate) fn from_bytes<T: CanonicalDeserialize>(bytes: &[u8]) -> Result<T, SerializationError> {
+    let mut reader = bytes;
+    let value = T::deserialize_compressed(&mut reader)?;
+    if !reader.is_empty() {
+        return Err(SerializationError::InvalidData);
+    }
+    Ok(value)
+}
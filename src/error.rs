@@ -0,0 +1,119 @@
+//! Crate-wide error type.
+//!
+//! Every fallible operation in the crate reports a `SchnorrError`, so that
+//! malformed input from the outside is rejected instead of panicking.
+
+use std::fmt;
+
+use ark_serialize::SerializationError;
+
+/// Errors returned by signing, verification, transcripts and decoding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchnorrError {
+    /// The public key is the identity point.
+    IdentityPublicKey,
+    /// The nonce commitment `R` cannot be used (identity or not absorbable).
+    MalformedR,
+    /// The verification equation `s·G == R + c·P` does not hold.
+    EquationMismatch,
+    /// Batch verification failed; holds the indices of the invalid entries.
+    BatchVerification(Vec<usize>),
+    /// The identity point was given where a transcript encoding is required.
+    IdentityPoint,
+    /// Two inputs that must describe the same participants differ in length.
+    LengthMismatch { expected: usize, found: usize },
+    /// Revealed nonces do not match their commitments; holds the participant indices.
+    NonceCommitmentMismatch(Vec<usize>),
+    /// Partial signatures failed verification; holds the signer indices.
+    InvalidPartialSignatures(Vec<usize>),
+    /// A public key appears more than once; holds the index of the repeat.
+    DuplicatePublicKey(usize),
+    /// A threshold is zero or larger than the number of participants.
+    InvalidThreshold { threshold: u32, participants: u32 },
+    /// A participant index is zero, repeated or unknown.
+    InvalidParticipantIndex(u32),
+    /// A DKG dealer's proof of knowledge of its secret does not verify.
+    InvalidProofOfKnowledge(u32),
+    /// A dealt share does not match the dealer's commitments; holds the dealer index.
+    InvalidShare(u32),
+    /// A ring signature was requested over an empty ring.
+    EmptyRing,
+    /// An adaptor secret does not match the pre-signature's adaptor point.
+    InvalidAdaptorSecret,
+    /// Dealt constant terms or a key share do not match the group public key.
+    GroupKeyMismatch,
+    /// The signer's public key is not part of the aggregated key set.
+    SignerNotInKeySet,
+    /// A hex string is malformed or does not encode a canonical field element.
+    InvalidHex,
+    /// Poseidon parameters are out of range or use a non-invertible S-box.
+    InvalidPoseidonParameters,
+    /// A byte encoding is malformed, not on the curve, or not canonical.
+    InvalidEncoding,
+}
+
+impl fmt::Display for SchnorrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchnorrError::IdentityPublicKey => write!(f, "public key is the identity point"),
+            SchnorrError::MalformedR => write!(f, "malformed nonce commitment R"),
+            SchnorrError::EquationMismatch => write!(f, "signature equation does not hold"),
+            SchnorrError::BatchVerification(indices) => {
+                write!(f, "invalid signatures at indices {indices:?}")
+            }
+            SchnorrError::IdentityPoint => write!(f, "unexpected identity point"),
+            SchnorrError::LengthMismatch { expected, found } => {
+                write!(f, "expected {expected} entries, found {found}")
+            }
+            SchnorrError::NonceCommitmentMismatch(indices) => {
+                write!(
+                    f,
+                    "nonce commitments do not match for participants {indices:?}"
+                )
+            }
+            SchnorrError::InvalidPartialSignatures(indices) => {
+                write!(f, "invalid partial signatures from signers {indices:?}")
+            }
+            SchnorrError::DuplicatePublicKey(index) => {
+                write!(f, "duplicate public key at index {index}")
+            }
+            SchnorrError::InvalidThreshold {
+                threshold,
+                participants,
+            } => {
+                write!(
+                    f,
+                    "invalid threshold {threshold} for {participants} participants"
+                )
+            }
+            SchnorrError::InvalidParticipantIndex(index) => {
+                write!(f, "invalid participant index {index}")
+            }
+            SchnorrError::InvalidProofOfKnowledge(index) => {
+                write!(f, "invalid proof of knowledge from dealer {index}")
+            }
+            SchnorrError::InvalidShare(index) => {
+                write!(f, "invalid share from dealer {index}")
+            }
+            SchnorrError::EmptyRing => write!(f, "empty ring"),
+            SchnorrError::InvalidAdaptorSecret => {
+                write!(f, "adaptor secret does not match the adaptor point")
+            }
+            SchnorrError::GroupKeyMismatch => {
+                write!(f, "shares do not match the group public key")
+            }
+            SchnorrError::SignerNotInKeySet => write!(f, "signer is not in the key set"),
+            SchnorrError::InvalidHex => write!(f, "invalid hex field element"),
+            SchnorrError::InvalidPoseidonParameters => write!(f, "invalid poseidon parameters"),
+            SchnorrError::InvalidEncoding => write!(f, "invalid byte encoding"),
+        }
+    }
+}
+
+impl std::error::Error for SchnorrError {}
+
+impl From<SerializationError> for SchnorrError {
+    fn from(_: SerializationError) -> Self {
+        SchnorrError::InvalidEncoding
+    }
+}
@@ -0,0 +1,454 @@
+//! FROST threshold Schnorr signatures using Poseidon for binding factors and challenges.
+//!
+//! Inspired by:
+//! - FROST (Komlo, Goldberg): https://eprint.iacr.org/2020/852.pdf
+//! - RFC 9591 two-round signing flow, adapted to Poseidon over Arkworks
+//!
+//! A `t`-of-`n` group key is Shamir-shared (see `shamir`). To sign, any `t`
+//! signers publish nonce commitments `(D_i, E_i)`, then each derives the
+//! binding factors `ρ_i` with `PoseidonSponge`, the group nonce
+//! `R = Σ (D_i + ρ_i·E_i)` and the challenge `c = H(R, Y, message)`, and
+//! answers with `z_i = d_i + ρ_i·e_i + λ_i·s_i·c`. The sum of the `z_i` is a
+//! plain `Signature` that verifies with `Signature::verify` under the group key.
+
+#![allow(non_snake_case)]
+
+use ark_ec::CurveGroup;
+use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use rand::rngs::OsRng;
+use std::fmt;
+
+use crate::{
+    error::SchnorrError,
+    keypair::Keypair,
+    poseidon_hash::sponge::PoseidonSponge,
+    shamir::{self, lagrange_coefficient},
+    signature::Signature,
+    transcript::{Message, PoseidonTranscript, poseidon_transcript},
+};
+
+/// Domain separator absorbed before hashing the commitment list ("frost" in ASCII).
+const BINDING_TAG: u64 = 0x66726f7374;
+
+/// A participant's share of the group signing key.
+///
+/// `keypair` holds the secret share `s_i` and the verification share `Y_i = s_i·G`.
+#[derive(Debug, Clone)]
+pub struct KeyShare<F: Field, G: CurveGroup> {
+    pub index: u32,
+    pub keypair: Keypair<F, G>,
+    pub group_public_key: G,
+}
+
+/// A signer's secret nonce pair `(d_i, e_i)`.
+///
+/// It is intentionally neither `Clone` nor serializable: `Session::sign`
+/// takes it by value, so a nonce cannot be used for two signatures. Its
+/// `Debug` output omits both scalars.
+pub struct SigningNonces<F: Field> {
+    d: F,
+    e: F,
+}
+
+impl<F: Field> fmt::Debug for SigningNonces<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SigningNonces").finish_non_exhaustive()
+    }
+}
+
+/// A signer's public nonce commitments `(D_i, E_i)`, shared in the first round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct SigningCommitment<G: CurveGroup> {
+    pub index: u32,
+    pub D: G,
+    pub E: G,
+}
+
+/// Splits `keypair` into `participants` key shares, any `threshold` of which can sign.
+///
+/// This is trusted-dealer key generation: whoever runs it learns the whole key.
+pub fn split_keypair<F: Field, G: CurveGroup<ScalarField = F>>(
+    keypair: &Keypair<F, G>,
+    threshold: u32,
+    participants: u32,
+) -> Result<Vec<KeyShare<F, G>>, SchnorrError> {
+    let shares = shamir::split_secret(keypair.private_key, threshold, participants)?;
+
+    Ok(shares
+        .into_iter()
+        .map(|share| KeyShare {
+            index: share.index,
+            keypair: Keypair::from_private_key(share.value),
+            group_public_key: keypair.public_key,
+        })
+        .collect())
+}
+
+/// Creates a fresh nonce pair and its commitments for the signer `index`.
+pub fn commit<F: Field, G: CurveGroup<ScalarField = F>>(
+    index: u32,
+) -> (SigningNonces<F>, SigningCommitment<G>) {
+    let mut rng = OsRng;
+    let d = F::rand(&mut rng);
+    let e = F::rand(&mut rng);
+    let commitment = SigningCommitment {
+        index,
+        D: G::generator() * d,
+        E: G::generator() * e,
+    };
+
+    (SigningNonces { d, e }, commitment)
+}
+
+/// A signing session binding the participating signers' commitments to one message.
+#[derive(Debug, Clone)]
+pub struct Session<F: Field, G: CurveGroup> {
+    group_public_key: G,
+    commitments: Vec<SigningCommitment<G>>,
+    binding_factors: Vec<F>,
+    lagrange_coefficients: Vec<F>,
+    R: G,
+    challenge: F,
+}
+
+impl<F: Field, G: CurveGroup<ScalarField = F>> Session<F, G> {
+    /// Starts a session for `message` signed by the owners of `commitments`.
+    ///
+    /// The commitment list is hashed sorted by index, as in RFC 9591, so the
+    /// session does not depend on the order of `commitments`; positions in
+    /// the other methods still follow that order. Fails with
+    /// `InvalidParticipantIndex` for zero or repeated indices and with
+    /// `MalformedR` if a commitment is the identity.
+    pub fn new(
+        group_public_key: G,
+        commitments: &[SigningCommitment<G>],
+        message: Message<'_, F>,
+    ) -> Result<Self, SchnorrError> {
+        let indices: Vec<u32> = commitments.iter().map(|c| c.index).collect();
+        let lagrange_coefficients = indices
+            .iter()
+            .map(|index| lagrange_coefficient(*index, &indices))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut sorted: Vec<&SigningCommitment<G>> = commitments.iter().collect();
+        sorted.sort_by_key(|commitment| commitment.index);
+
+        let mut binding_transcript = poseidon_transcript::<F, G>();
+        binding_transcript.absorb_scalar(F::from(BINDING_TAG));
+        binding_transcript.absorb_point(group_public_key)?;
+        for commitment in sorted {
+            binding_transcript.absorb_scalar(F::from(commitment.index));
+            binding_transcript
+                .absorb_point(commitment.D)
+                .map_err(|_| SchnorrError::MalformedR)?;
+            binding_transcript
+                .absorb_point(commitment.E)
+                .map_err(|_| SchnorrError::MalformedR)?;
+        }
+        binding_transcript.absorb_message(message);
+        let digest = binding_transcript.squeeze_challenge();
+
+        let binding_factors: Vec<F> = indices
+            .iter()
+            .map(|index| {
+                let mut sponge = PoseidonSponge::<F>::new();
+                sponge.update(&[digest, F::from(*index)]);
+                sponge.squeeze()
+            })
+            .collect();
+
+        let R = commitments
+            .iter()
+            .zip(&binding_factors)
+            .map(|(commitment, rho)| commitment.D + commitment.E * rho)
+            .sum();
+        let mut transcript = PoseidonTranscript::new();
+        let challenge = Signature::challenge(&mut transcript, R, group_public_key, message)?;
+
+        Ok(Session {
+            group_public_key,
+            commitments: commitments.to_vec(),
+            binding_factors,
+            lagrange_coefficients,
+            R,
+            challenge,
+        })
+    }
+
+    /// Returns the group public key the final signature verifies under.
+    pub fn group_public_key(&self) -> G {
+        self.group_public_key
+    }
+
+    /// Returns the group nonce `R = Σ (D_i + ρ_i·E_i)`.
+    pub fn final_nonce(&self) -> G {
+        self.R
+    }
+
+    /// Returns the binding factor `ρ_i` of the signer at `position`.
+    pub fn binding_factor(&self, position: usize) -> Option<F> {
+        self.binding_factors.get(position).copied()
+    }
+
+    /// Computes the signature share `z_i = d_i + ρ_i·e_i + λ_i·s_i·c`, consuming the nonces.
+    ///
+    /// Fails with `GroupKeyMismatch` if the share belongs to another group,
+    /// with `SignerNotInKeySet` if the share's index has no commitment in this
+    /// session, and with `NonceCommitmentMismatch` if `nonces` do not open
+    /// that commitment.
+    pub fn sign(
+        &self,
+        share: &KeyShare<F, G>,
+        nonces: SigningNonces<F>,
+    ) -> Result<F, SchnorrError> {
+        if share.group_public_key != self.group_public_key {
+            return Err(SchnorrError::GroupKeyMismatch);
+        }
+        let position = self
+            .commitments
+            .iter()
+            .position(|commitment| commitment.index == share.index)
+            .ok_or(SchnorrError::SignerNotInKeySet)?;
+        let commitment = &self.commitments[position];
+        if G::generator() * nonces.d != commitment.D || G::generator() * nonces.e != commitment.E {
+            return Err(SchnorrError::NonceCommitmentMismatch(vec![position]));
+        }
+
+        Ok(nonces.d
+            + self.binding_factors[position] * nonces.e
+            + self.lagrange_coefficients[position] * share.keypair.private_key * self.challenge)
+    }
+
+    /// Verifies the signature share of the signer at `position` against their
+    /// verification share `Y_i`, checking `z_i·G == D_i + ρ_i·E_i + c·λ_i·Y_i`.
+    pub fn verify_share(
+        &self,
+        position: usize,
+        signature_share: F,
+        verification_share: G,
+    ) -> Result<(), SchnorrError> {
+        let commitment = self
+            .commitments
+            .get(position)
+            .ok_or(SchnorrError::SignerNotInKeySet)?;
+        let lhs = G::generator() * signature_share;
+        let rhs = commitment.D
+            + commitment.E * self.binding_factors[position]
+            + verification_share * (self.challenge * self.lagrange_coefficients[position]);
+
+        if lhs != rhs {
+            return Err(SchnorrError::EquationMismatch);
+        }
+        Ok(())
+    }
+
+    /// Verifies every signature share and sums them into a Schnorr signature.
+    ///
+    /// `signature_shares` and `verification_shares` are indexed like the
+    /// session's commitments. Fails with `InvalidPartialSignatures` listing the
+    /// positions of every share that does not verify.
+    pub fn aggregate(
+        &self,
+        signature_shares: &[F],
+        verification_shares: &[G],
+    ) -> Result<Signature<F, G>, SchnorrError> {
+        for len in [signature_shares.len(), verification_shares.len()] {
+            if len != self.commitments.len() {
+                return Err(SchnorrError::LengthMismatch {
+                    expected: self.commitments.len(),
+                    found: len,
+                });
+            }
+        }
+
+        let invalid: Vec<usize> = signature_shares
+            .iter()
+            .zip(verification_shares)
+            .enumerate()
+            .filter(|(position, (z, Y))| self.verify_share(*position, **z, **Y).is_err())
+            .map(|(position, _)| position)
+            .collect();
+        if !invalid.is_empty() {
+            return Err(SchnorrError::InvalidPartialSignatures(invalid));
+        }
+
+        Ok(Signature {
+            R: self.R,
+            s: signature_shares.iter().sum(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    type F = ark_bn254::Fr;
+    type G = ark_bn254::G1Projective;
+
+    /// Signs `message` with the given subset of shares and returns the session and shares.
+    fn sign_with(shares: &[&KeyShare<F, G>], message: F) -> (Session<F, G>, Vec<F>) {
+        let (nonces, commitments): (Vec<_>, Vec<_>) = shares
+            .iter()
+            .map(|share| commit::<F, G>(share.index))
+            .unzip();
+        let session = Session::new(
+            shares[0].group_public_key,
+            &commitments,
+            Message::Scalar(message),
+        )
+        .unwrap();
+        let signature_shares = shares
+            .iter()
+            .zip(nonces)
+            .map(|(share, nonces)| session.sign(share, nonces).unwrap())
+            .collect();
+
+        (session, signature_shares)
+    }
+
+    #[test]
+    fn test_frost_threshold_signature_valid() {
+        let keypair = Keypair::<F, G>::generate();
+        let shares = split_keypair(&keypair, 3, 5).unwrap();
+        let message = F::from(16);
+
+        for signers in [[0, 1, 2], [4, 2, 0], [1, 3, 4]] {
+            let signers: Vec<_> = signers.iter().map(|i| &shares[*i]).collect();
+            let (session, signature_shares) = sign_with(&signers, message);
+            let verification_shares: Vec<_> =
+                signers.iter().map(|s| s.keypair.public_key).collect();
+            let signature = session
+                .aggregate(&signature_shares, &verification_shares)
+                .unwrap();
+
+            let mut transcript = PoseidonTranscript::new();
+            assert!(
+                signature
+                    .verify(keypair.public_key, &mut transcript, message)
+                    .is_ok()
+            );
+        }
+    }
+
+    #[test]
+    fn test_frost_session_order_independent() {
+        let keypair = Keypair::<F, G>::generate();
+        let shares = split_keypair(&keypair, 3, 5).unwrap();
+        let message = F::from(16);
+        let signers = [&shares[0], &shares[2], &shares[4]];
+        let (nonces, commitments): (Vec<_>, Vec<_>) = signers
+            .iter()
+            .map(|share| commit::<F, G>(share.index))
+            .unzip();
+
+        let sorted =
+            Session::new(keypair.public_key, &commitments, Message::Scalar(message)).unwrap();
+        let shuffled = [commitments[2], commitments[0], commitments[1]];
+        let shuffled =
+            Session::new(keypair.public_key, &shuffled, Message::Scalar(message)).unwrap();
+        assert_eq!(sorted.final_nonce(), shuffled.final_nonce());
+        assert_eq!(sorted.binding_factor(0), shuffled.binding_factor(1));
+        assert_eq!(sorted.binding_factor(2), shuffled.binding_factor(0));
+
+        // Signers that saw the list in different orders still produce one signature.
+        let mut nonces = nonces.into_iter();
+        let s = sorted.sign(signers[0], nonces.next().unwrap()).unwrap()
+            + shuffled.sign(signers[1], nonces.next().unwrap()).unwrap()
+            + shuffled.sign(signers[2], nonces.next().unwrap()).unwrap();
+        let signature = Signature {
+            R: sorted.final_nonce(),
+            s,
+        };
+        let mut transcript = PoseidonTranscript::new();
+        assert!(
+            signature
+                .verify(keypair.public_key, &mut transcript, message)
+                .is_ok()
+        );
+
+        let repeated = [commitments[0], commitments[1], commitments[0]];
+        assert_eq!(
+            Session::new(keypair.public_key, &repeated, Message::Scalar(message)).err(),
+            Some(SchnorrError::InvalidParticipantIndex(1))
+        );
+    }
+
+    #[test]
+    fn test_frost_too_few_signers_fails() {
+        let keypair = Keypair::<F, G>::generate();
+        let shares = split_keypair(&keypair, 3, 5).unwrap();
+        let message = F::from(16);
+
+        let (session, signature_shares) = sign_with(&[&shares[0], &shares[1]], message);
+        let signature = Signature {
+            R: session.final_nonce(),
+            s: signature_shares.iter().sum(),
+        };
+
+        let mut transcript = PoseidonTranscript::new();
+        assert_eq!(
+            signature.verify(keypair.public_key, &mut transcript, message),
+            Err(SchnorrError::EquationMismatch)
+        );
+    }
+
+    #[test]
+    fn test_frost_reports_bad_share() {
+        let keypair = Keypair::<F, G>::generate();
+        let shares = split_keypair(&keypair, 2, 3).unwrap();
+
+        let signers = [&shares[0], &shares[2]];
+        let (session, mut signature_shares) = sign_with(&signers, F::from(16));
+        signature_shares[1] += F::from(1);
+
+        let verification_shares = [shares[0].keypair.public_key, shares[2].keypair.public_key];
+        assert_eq!(
+            session
+                .aggregate(&signature_shares, &verification_shares)
+                .err(),
+            Some(SchnorrError::InvalidPartialSignatures(vec![1]))
+        );
+    }
+
+    #[test]
+    fn test_frost_rejects_share_of_other_group() {
+        let keypair = Keypair::<F, G>::generate();
+        let other = Keypair::<F, G>::generate();
+        let foreign = split_keypair(&other, 2, 2).unwrap();
+        let (nonces, first) = commit::<F, G>(1);
+        let (_, second) = commit::<F, G>(2);
+
+        let session = Session::new(
+            keypair.public_key,
+            &[first, second],
+            Message::Scalar(F::from(16)),
+        )
+        .unwrap();
+        assert_eq!(
+            session.sign(&foreign[0], nonces),
+            Err(SchnorrError::GroupKeyMismatch)
+        );
+    }
+
+    #[test]
+    fn test_frost_rejects_foreign_nonces() {
+        let keypair = Keypair::<F, G>::generate();
+        let shares = split_keypair(&keypair, 2, 2).unwrap();
+        let (_, first) = commit::<F, G>(1);
+        let (_, second) = commit::<F, G>(2);
+        let (other_nonces, _) = commit::<F, G>(1);
+
+        let session = Session::new(
+            keypair.public_key,
+            &[first, second],
+            Message::Scalar(F::from(16)),
+        )
+        .unwrap();
+        assert_eq!(
+            session.sign(&shares[0], other_nonces),
+            Err(SchnorrError::NonceCommitmentMismatch(vec![0]))
+        );
+    }
+}
@@ -0,0 +1,532 @@
+//! Reusable key aggregation context for MuSig and MuSig2.
+//!
+//! `KeyAggContext` computes the keyset hash, every signer's aggregation
+//! coefficient `a_i = H(keyset_hash, P_i)` and the aggregate key
+//! `Σ a_i·P_i` once, so signing sessions over the same group look them up
+//! instead of re-hashing through fresh transcripts.
+//!
+//! `KeyAggOptions` makes the aggregate key independent of the order in which
+//! members are listed (lexicographic sorting of compressed keys) and decides
+//! how repeated keys are treated.
+//!
+//! The aggregate key can be tweaked, plainly or x-only, to derive child keys
+//! the group can still sign for. Following BIP-327, the context tracks the
+//! sign accumulator `gacc` and tweak accumulator `tacc` with
+//! `Q = gacc·Σ a_i·P_i + tacc·G`; partial signatures use `gacc·a_i·x_i` and
+//! aggregation adds `c·tacc`.
+
+use ark_ec::CurveGroup;
+use ark_ff::Field;
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError, Valid, Validate,
+};
+use ark_std::io::{Read, Write};
+
+use crate::{encoding, error::SchnorrError, musig::MuSig, tweak};
+
+/// How key aggregation treats a public key that appears more than once.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicateKeys {
+    /// Duplicates are aggregated like any other key.
+    #[default]
+    Allow,
+    /// Aggregation fails with `DuplicatePublicKey`.
+    Reject,
+    /// Duplicates are allowed and, as in MuSig2 (BIP-327), every occurrence of
+    /// the first key that differs from the first listed key gets coefficient 1,
+    /// saving one scalar multiplication per signature.
+    SecondUniqueKey,
+}
+
+/// Options controlling key order and duplicate handling during aggregation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeyAggOptions {
+    /// Sort keys lexicographically by their compressed encoding first.
+    pub sort: bool,
+    /// Treatment of repeated keys.
+    pub duplicates: DuplicateKeys,
+}
+
+/// Sorts public keys lexicographically by their compressed encoding.
+pub fn sort_pub_keys<G: CurveGroup>(pub_keys: &[G]) -> Vec<G> {
+    let mut sorted = pub_keys.to_vec();
+    sorted.sort_by_cached_key(encoding::to_bytes);
+    sorted
+}
+
+/// The aggregated key of a signer group together with its per-key coefficients.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyAggContext<F: Field, G: CurveGroup> {
+    options: KeyAggOptions,
+    pub_keys: Vec<G>,
+    keyset_hash: F,
+    coefficients: Vec<F>,
+    agg_pub_key: G,
+    gacc: F,
+    tacc: F,
+}
+
+impl<F: Field, G: CurveGroup<ScalarField = F>> KeyAggContext<F, G> {
+    /// Aggregates `pub_keys` in the given order with default options.
+    ///
+    /// Fails with `IdentityPoint` if a key is the identity and with
+    /// `IdentityPublicKey` if the aggregate key is.
+    pub fn new(pub_keys: &[G]) -> Result<Self, SchnorrError> {
+        Self::with_options(pub_keys, KeyAggOptions::default())
+    }
+
+    /// Aggregates `pub_keys` after applying the sorting and duplicate options.
+    ///
+    /// With `DuplicateKeys::Reject`, fails with `DuplicatePublicKey` holding the
+    /// index of the first repeated key in aggregation order.
+    pub fn with_options(pub_keys: &[G], options: KeyAggOptions) -> Result<Self, SchnorrError> {
+        let pub_keys = if options.sort {
+            sort_pub_keys(pub_keys)
+        } else {
+            pub_keys.to_vec()
+        };
+        if options.duplicates == DuplicateKeys::Reject
+            && let Some(index) =
+                (1..pub_keys.len()).find(|&index| pub_keys[..index].contains(&pub_keys[index]))
+        {
+            return Err(SchnorrError::DuplicatePublicKey(index));
+        }
+        let second_key = match options.duplicates {
+            DuplicateKeys::SecondUniqueKey => pub_keys.iter().find(|key| **key != pub_keys[0]),
+            _ => None,
+        };
+
+        let keyset_hash = MuSig::<F, G>::keyset_challenge(&pub_keys)?;
+        let coefficients = pub_keys
+            .iter()
+            .map(|pub_key| match second_key {
+                Some(second_key) if pub_key == second_key => Ok(F::ONE),
+                _ => MuSig::<F, G>::key_coefficient(keyset_hash, *pub_key),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let agg_pub_key = pub_keys
+            .iter()
+            .zip(&coefficients)
+            .map(|(pub_key, coefficient)| *pub_key * coefficient)
+            .sum::<G>();
+        if agg_pub_key.is_zero() {
+            return Err(SchnorrError::IdentityPublicKey);
+        }
+
+        Ok(KeyAggContext {
+            options,
+            pub_keys,
+            keyset_hash,
+            coefficients,
+            agg_pub_key,
+            gacc: F::ONE,
+            tacc: F::ZERO,
+        })
+    }
+
+    /// Tweaks the aggregate key to `Q' = g·Q + t·G` for the tweak `t`.
+    ///
+    /// `g` is `-1` when `x_only` is set and `Q` has odd y, and `1` otherwise.
+    /// Fails with `IdentityPublicKey` if the tweaked key is the identity.
+    pub fn tweak(&self, tweak: F, x_only: bool) -> Result<Self, SchnorrError> {
+        let sign = if x_only && !tweak::has_even_y(self.agg_pub_key) {
+            -F::ONE
+        } else {
+            F::ONE
+        };
+        let agg_pub_key = self.agg_pub_key * sign + G::generator() * tweak;
+        if agg_pub_key.is_zero() {
+            return Err(SchnorrError::IdentityPublicKey);
+        }
+
+        Ok(KeyAggContext {
+            agg_pub_key,
+            gacc: sign * self.gacc,
+            tacc: tweak + sign * self.tacc,
+            ..self.clone()
+        })
+    }
+
+    /// Tweaks the aggregate key by `t = H(Q, data)`, where `Q` is the current
+    /// aggregate key (its even-y representative with `x_only`).
+    pub fn tweak_by_hash(&self, data: &[u8], x_only: bool) -> Result<Self, SchnorrError> {
+        let agg_pub_key = if x_only {
+            tweak::even_y(self.agg_pub_key)
+        } else {
+            self.agg_pub_key
+        };
+        let tweak = tweak::hash_tweak(agg_pub_key, data)?;
+
+        self.tweak(tweak, x_only)
+    }
+
+    /// Returns the signers' public keys in aggregation order.
+    pub fn pub_keys(&self) -> &[G] {
+        &self.pub_keys
+    }
+
+    /// Returns the keyset hash binding every coefficient to this group.
+    pub fn keyset_hash(&self) -> F {
+        self.keyset_hash
+    }
+
+    /// Returns the aggregation coefficients, indexed like `pub_keys`.
+    pub fn coefficients(&self) -> &[F] {
+        &self.coefficients
+    }
+
+    /// Returns the aggregate public key, including any tweaks.
+    pub fn agg_pub_key(&self) -> G {
+        self.agg_pub_key
+    }
+
+    /// Returns the sign accumulator `gacc` (`±1`) applied to every secret key.
+    pub fn gacc(&self) -> F {
+        self.gacc
+    }
+
+    /// Returns the tweak accumulator `tacc` added to the aggregate secret.
+    pub fn tacc(&self) -> F {
+        self.tacc
+    }
+
+    /// Returns the options the group was aggregated with.
+    pub fn options(&self) -> KeyAggOptions {
+        self.options
+    }
+
+    /// Returns the first position of `pub_key` in the group.
+    pub fn index_of(&self, pub_key: &G) -> Option<usize> {
+        self.pub_keys.iter().position(|key| key == pub_key)
+    }
+
+    /// Returns the aggregation coefficient of `pub_key`.
+    pub fn coefficient(&self, pub_key: &G) -> Result<F, SchnorrError> {
+        self.index_of(pub_key)
+            .map(|index| self.coefficients[index])
+            .ok_or(SchnorrError::SignerNotInKeySet)
+    }
+
+    /// Encodes the context as the options, the compressed key list, keyset hash,
+    /// coefficients, aggregate key and tweak accumulators.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encoding::to_bytes(self)
+    }
+
+    /// Decodes a context produced by `to_bytes`.
+    ///
+    /// The keyset hash and coefficients are recomputed from the key list and
+    /// options and must match the encoded values, and the aggregate key must
+    /// equal `gacc·Σ a_i·P_i + tacc·G` with `gacc = ±1`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SchnorrError> {
+        Ok(encoding::from_bytes(bytes)?)
+    }
+}
+
+impl DuplicateKeys {
+    fn to_byte(self) -> u8 {
+        match self {
+            DuplicateKeys::Allow => 0,
+            DuplicateKeys::Reject => 1,
+            DuplicateKeys::SecondUniqueKey => 2,
+        }
+    }
+
+    fn from_byte(byte: u8) -> Result<Self, SerializationError> {
+        match byte {
+            0 => Ok(DuplicateKeys::Allow),
+            1 => Ok(DuplicateKeys::Reject),
+            2 => Ok(DuplicateKeys::SecondUniqueKey),
+            _ => Err(SerializationError::InvalidData),
+        }
+    }
+}
+
+impl CanonicalSerialize for KeyAggOptions {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.sort.serialize_with_mode(&mut writer, compress)?;
+        self.duplicates
+            .to_byte()
+            .serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.sort.serialized_size(compress) + self.duplicates.to_byte().serialized_size(compress)
+    }
+}
+
+impl Valid for KeyAggOptions {
+    fn check(&self) -> Result<(), SerializationError> {
+        Ok(())
+    }
+}
+
+impl CanonicalDeserialize for KeyAggOptions {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let sort = bool::deserialize_with_mode(&mut reader, compress, validate)?;
+        let duplicates =
+            DuplicateKeys::from_byte(u8::deserialize_with_mode(&mut reader, compress, validate)?)?;
+
+        Ok(KeyAggOptions { sort, duplicates })
+    }
+}
+
+impl<F: Field, G: CurveGroup<ScalarField = F>> CanonicalSerialize for KeyAggContext<F, G> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.options.serialize_with_mode(&mut writer, compress)?;
+        self.pub_keys.serialize_with_mode(&mut writer, compress)?;
+        self.keyset_hash
+            .serialize_with_mode(&mut writer, compress)?;
+        self.coefficients
+            .serialize_with_mode(&mut writer, compress)?;
+        self.agg_pub_key
+            .serialize_with_mode(&mut writer, compress)?;
+        self.gacc.serialize_with_mode(&mut writer, compress)?;
+        self.tacc.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.options.serialized_size(compress)
+            + self.pub_keys.serialized_size(compress)
+            + self.keyset_hash.serialized_size(compress)
+            + self.coefficients.serialized_size(compress)
+            + self.agg_pub_key.serialized_size(compress)
+            + self.gacc.serialized_size(compress)
+            + self.tacc.serialized_size(compress)
+    }
+}
+
+impl<F: Field, G: CurveGroup<ScalarField = F>> Valid for KeyAggContext<F, G> {
+    fn check(&self) -> Result<(), SerializationError> {
+        let untweaked = KeyAggContext::with_options(&self.pub_keys, self.options)
+            .map_err(|_| SerializationError::InvalidData)?;
+        let expected = KeyAggContext {
+            agg_pub_key: untweaked.agg_pub_key * self.gacc + G::generator() * self.tacc,
+            gacc: self.gacc,
+            tacc: self.tacc,
+            ..untweaked
+        };
+        if (self.gacc != F::ONE && self.gacc != -F::ONE) || expected != *self {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(())
+    }
+}
+
+impl<F: Field, G: CurveGroup<ScalarField = F>> CanonicalDeserialize for KeyAggContext<F, G> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let options = KeyAggOptions::deserialize_with_mode(&mut reader, compress, validate)?;
+        let pub_keys = Vec::<G>::deserialize_with_mode(&mut reader, compress, validate)?;
+        let keyset_hash = F::deserialize_with_mode(&mut reader, compress, validate)?;
+        let coefficients = Vec::<F>::deserialize_with_mode(&mut reader, compress, validate)?;
+        let agg_pub_key = G::deserialize_with_mode(&mut reader, compress, validate)?;
+        let gacc = F::deserialize_with_mode(&mut reader, compress, validate)?;
+        let tacc = F::deserialize_with_mode(&mut reader, compress, validate)?;
+        let context = KeyAggContext {
+            options,
+            pub_keys,
+            keyset_hash,
+            coefficients,
+            agg_pub_key,
+            gacc,
+            tacc,
+        };
+        if let Validate::Yes = validate {
+            context.check()?;
+        }
+        Ok(context)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::keypair::Keypair;
+    use ark_ec::PrimeGroup;
+
+    type F = ark_bn254::Fr;
+    type G = ark_bn254::G1Projective;
+
+    fn pub_keys(n: usize) -> Vec<G> {
+        (0..n)
+            .map(|_| Keypair::<F, G>::generate().public_key)
+            .collect()
+    }
+
+    #[test]
+    fn test_key_agg_matches_musig() {
+        let pub_keys = pub_keys(3);
+        let context = KeyAggContext::<F, G>::new(&pub_keys).unwrap();
+
+        let keyset_challenge = MuSig::<F, G>::keyset_challenge(&pub_keys).unwrap();
+        assert_eq!(context.keyset_hash(), keyset_challenge);
+        assert_eq!(
+            context.agg_pub_key(),
+            MuSig::<F, G>::agg_pub_keys(&pub_keys, keyset_challenge).unwrap()
+        );
+        for pub_key in &pub_keys {
+            assert_eq!(
+                context.coefficient(pub_key).unwrap(),
+                MuSig::<F, G>::key_coefficient(keyset_challenge, *pub_key).unwrap()
+            );
+        }
+        assert_eq!(context.index_of(&pub_keys[2]), Some(2));
+        assert_eq!(
+            context.coefficient(&Keypair::<F, G>::generate().public_key),
+            Err(SchnorrError::SignerNotInKeySet)
+        );
+    }
+
+    #[test]
+    fn test_key_agg_bytes_round_trip() {
+        let context = KeyAggContext::<F, G>::new(&pub_keys(3)).unwrap();
+        let bytes = context.to_bytes();
+        assert_eq!(KeyAggContext::from_bytes(&bytes).unwrap(), context);
+
+        // Swapping two keys keeps every point valid but breaks the cached hash.
+        let mut swapped = context.clone();
+        swapped.pub_keys.swap(0, 1);
+        assert_eq!(
+            KeyAggContext::<F, G>::from_bytes(&swapped.to_bytes()),
+            Err(SchnorrError::InvalidEncoding)
+        );
+    }
+
+    #[test]
+    fn test_key_agg_sorting_ignores_order() {
+        let pub_keys = pub_keys(4);
+        let mut reversed = pub_keys.clone();
+        reversed.reverse();
+        let options = KeyAggOptions {
+            sort: true,
+            ..Default::default()
+        };
+
+        let context = KeyAggContext::<F, G>::with_options(&pub_keys, options).unwrap();
+        let reversed_context = KeyAggContext::<F, G>::with_options(&reversed, options).unwrap();
+        assert_eq!(context, reversed_context);
+        assert_eq!(context.pub_keys(), sort_pub_keys(&pub_keys));
+        assert_ne!(
+            KeyAggContext::<F, G>::new(&pub_keys).unwrap().agg_pub_key(),
+            KeyAggContext::<F, G>::new(&reversed).unwrap().agg_pub_key()
+        );
+        assert_eq!(
+            KeyAggContext::<F, G>::from_bytes(&context.to_bytes()).unwrap(),
+            context
+        );
+    }
+
+    #[test]
+    fn test_key_agg_rejects_duplicates() {
+        let pub_keys = pub_keys(3);
+        let options = KeyAggOptions {
+            sort: false,
+            duplicates: DuplicateKeys::Reject,
+        };
+
+        assert!(KeyAggContext::<F, G>::with_options(&pub_keys, options).is_ok());
+        assert_eq!(
+            KeyAggContext::<F, G>::with_options(
+                &[pub_keys[0], pub_keys[1], pub_keys[2], pub_keys[1]],
+                options
+            ),
+            Err(SchnorrError::DuplicatePublicKey(3))
+        );
+    }
+
+    #[test]
+    fn test_key_agg_second_unique_key() {
+        let keys = pub_keys(2);
+        let pub_keys = [keys[0], keys[0], keys[1], keys[1]];
+        let options = KeyAggOptions {
+            sort: false,
+            duplicates: DuplicateKeys::SecondUniqueKey,
+        };
+        let context = KeyAggContext::<F, G>::with_options(&pub_keys, options).unwrap();
+
+        let first_coefficient = context.coefficients()[0];
+        assert_ne!(first_coefficient, F::ONE);
+        assert_eq!(
+            context.coefficients(),
+            [first_coefficient, first_coefficient, F::ONE, F::ONE]
+        );
+        assert_eq!(
+            context.agg_pub_key(),
+            keys[0] * (first_coefficient + first_coefficient) + keys[1] * F::from(2)
+        );
+        assert_eq!(
+            KeyAggContext::<F, G>::from_bytes(&context.to_bytes()).unwrap(),
+            context
+        );
+    }
+
+    #[test]
+    fn test_key_agg_tweaked_musig2_signature() {
+        use crate::{
+            musig2::{AggNonce, Session, create_nonce},
+            transcript::{Message, PoseidonTranscript},
+        };
+
+        let keypairs: Vec<_> = (0..3).map(|_| Keypair::<F, G>::generate()).collect();
+        let pub_keys: Vec<_> = keypairs.iter().map(|k| k.public_key).collect();
+        let untweaked = KeyAggContext::<F, G>::new(&pub_keys).unwrap();
+        let context = untweaked
+            .tweak(F::from(7), false)
+            .unwrap()
+            .tweak_by_hash(b"taproot", true)
+            .unwrap()
+            .tweak(F::from(9), true)
+            .unwrap();
+
+        assert!(tweak::has_even_y(
+            context.agg_pub_key() - G::generator() * F::from(9)
+        ));
+        assert_eq!(
+            context.agg_pub_key(),
+            untweaked.agg_pub_key() * context.gacc() + G::generator() * context.tacc()
+        );
+        assert_eq!(
+            KeyAggContext::<F, G>::from_bytes(&context.to_bytes()).unwrap(),
+            context
+        );
+
+        let (sec_nonces, pub_nonces): (Vec<_>, Vec<_>) =
+            (0..3).map(|_| create_nonce::<F, G>()).unzip();
+        let message = F::from(16);
+        let session = Session::new(
+            &context,
+            AggNonce::aggregate(&pub_nonces),
+            Message::Scalar(message),
+        )
+        .unwrap();
+        let partial_sigs: Vec<_> = keypairs
+            .iter()
+            .zip(sec_nonces)
+            .map(|(keypair, sec_nonce)| session.partial_sign(keypair, sec_nonce).unwrap())
+            .collect();
+        let signature = session.aggregate(&partial_sigs);
+
+        let mut transcript = PoseidonTranscript::new();
+        assert!(
+            signature
+                .verify(context.agg_pub_key(), &mut transcript, message)
+                .is_ok()
+        );
+    }
+}
@@ -1,19 +1,42 @@
 use ark_ec::CurveGroup;
 use ark_ff::Field;
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError, Valid, Validate,
+};
+use ark_std::io::{Read, Write};
 use rand::rngs::OsRng;
+use std::fmt;
+
+use crate::{encoding, error::SchnorrError, tweak};
 
 /// A Schnorr keypair with a private scalar and public curve point.
-#[derive(Debug, Clone)]
+///
+/// `Debug` only prints the public key.
+#[derive(Clone)]
 pub struct Keypair<F: Field, G: CurveGroup> {
     pub private_key: F,
     pub public_key: G,
 }
 
+impl<F: Field, G: CurveGroup> fmt::Debug for Keypair<F, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Keypair")
+            .field("public_key", &self.public_key)
+            .finish_non_exhaustive()
+    }
+}
+
 impl<F: Field, G: CurveGroup<ScalarField = F>> Keypair<F, G> {
     /// Generates a random keypair.
     pub fn generate() -> Self {
         let mut rng = OsRng;
         let private_key = F::rand(&mut rng);
+
+        Self::from_private_key(private_key)
+    }
+
+    /// Builds a keypair from an existing private scalar.
+    pub fn from_private_key(private_key: F) -> Self {
         let public_key = G::generator() * private_key;
 
         Keypair {
@@ -21,4 +44,96 @@ impl<F: Field, G: CurveGroup<ScalarField = F>> Keypair<F, G> {
             public_key,
         }
     }
+
+    /// Derives the child keypair `(x + t, P + t·G)` for the tweak `t`.
+    ///
+    /// With `x_only`, the key is first replaced by its even-y representative
+    /// (negating the private key if needed), so the child only depends on the
+    /// x-coordinate of `P`. Fails with `IdentityPublicKey` if the child key is
+    /// the identity.
+    pub fn tweak(&self, tweak: F, x_only: bool) -> Result<Self, SchnorrError> {
+        let private_key = if x_only && !tweak::has_even_y(self.public_key) {
+            -self.private_key
+        } else {
+            self.private_key
+        };
+        let private_key = private_key + tweak;
+        if private_key.is_zero() {
+            return Err(SchnorrError::IdentityPublicKey);
+        }
+
+        Ok(Self::from_private_key(private_key))
+    }
+
+    /// Tweaks the keypair by `t = H(P, data)`, where `P` is the public key
+    /// (its even-y representative with `x_only`).
+    pub fn tweak_by_hash(&self, data: &[u8], x_only: bool) -> Result<Self, SchnorrError> {
+        let public_key = if x_only {
+            tweak::even_y(self.public_key)
+        } else {
+            self.public_key
+        };
+        let tweak = tweak::hash_tweak(public_key, data)?;
+
+        self.tweak(tweak, x_only)
+    }
+
+    /// Encodes the keypair as `private_key || compressed public_key`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encoding::to_bytes(self)
+    }
+
+    /// Decodes a keypair produced by `to_bytes`.
+    ///
+    /// Besides the point and scalar checks, the public key must match the
+    /// private key.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SchnorrError> {
+        Ok(encoding::from_bytes(bytes)?)
+    }
+}
+
+impl<F: Field, G: CurveGroup<ScalarField = F>> CanonicalSerialize for Keypair<F, G> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.private_key
+            .serialize_with_mode(&mut writer, compress)?;
+        self.public_key.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.private_key.serialized_size(compress) + self.public_key.serialized_size(compress)
+    }
+}
+
+impl<F: Field, G: CurveGroup<ScalarField = F>> Valid for Keypair<F, G> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.private_key.check()?;
+        self.public_key.check()?;
+        if G::generator() * self.private_key != self.public_key {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(())
+    }
+}
+
+impl<F: Field, G: CurveGroup<ScalarField = F>> CanonicalDeserialize for Keypair<F, G> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let private_key = F::deserialize_with_mode(&mut reader, compress, validate)?;
+        let public_key = G::deserialize_with_mode(&mut reader, compress, validate)?;
+        let keypair = Keypair {
+            private_key,
+            public_key,
+        };
+        if let Validate::Yes = validate {
+            keypair.check()?;
+        }
+        Ok(keypair)
+    }
 }
@@ -1,18 +1,44 @@
 //! Schnorr signatures over elliptic curves with Poseidon-based transcripts.
 //!
 //! Modules:
+//! - `adaptor`: Adaptor signatures for atomic swaps and payment channels
+//! - `blind`: Clause blind Schnorr signatures for anonymous credentials
+//! - `encoding`: Fixed-length byte encodings
+//! - `dkg`: Pedersen distributed key generation with Feldman commitments
+//! - `error`: Crate-wide error type
 //! - `keypair`: Key generation
 //! - `signature`: Basic Schnorr signature
+//! - `frost`: FROST threshold Schnorr signatures
+//! - `key_agg`: Reusable key aggregation context for MuSig and MuSig2
 //! - `musig`: Multi-signature (MuSig) support
+//! - `musig2`: Two-round MuSig2 multi-signatures with pre-shareable nonces
+//! - `refresh`: Proactive share refresh and signer-set resharing
+//! - `ring`: AOS ring signatures with optional linkable key images
+//! - `session`: Typestate MuSig signing session with nonce-reuse protection
 //! - `poseidon_hash`: Native Poseidon sponge hash
+//! - `shamir`: Shamir secret sharing and Lagrange interpolation
 //! - `transcript`: Fiat–Shamir transcript abstraction
+//! - `tweak`: Even-y normalisation and hashed tweaks for child keys
 //! - `test`: Unit tests
 //!
 //! Inspired by ZK-friendly signature systems and built over the Arkworks ecosystem.
 
+pub mod adaptor;
+pub mod blind;
+pub mod dkg;
+mod encoding;
+pub mod error;
+pub mod frost;
+pub mod key_agg;
 pub mod keypair;
 pub mod musig;
+pub mod musig2;
 pub mod poseidon_hash;
+pub mod refresh;
+pub mod ring;
+pub mod session;
+pub mod shamir;
 pub mod signature;
 pub mod test;
 pub mod transcript;
+mod tweak;
@@ -8,50 +8,108 @@
 //! - Keyset hashing for binding public keys
 //! - Public key aggregation
 //! - Nonce generation
-//! - Signature aggregation and verification
+//! - Nonce commitments for the three-round MuSig flow (commit, reveal, sign)
+//! - Partial signature verification and aggregation that names misbehaving signers
+//! - Signature aggregation and verification over scalar, slice and byte messages,
+//!   using the same challenge as `Signature` so aggregates verify as plain signatures
+//! - Canonical serialization of aggregated signatures
 
 #![allow(non_snake_case)]
 
 use ark_ec::CurveGroup;
 use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 
 use crate::{
+    encoding,
+    error::SchnorrError,
+    key_agg::KeyAggContext,
     keypair::Keypair,
-    transcript::{Transcript, poseidon_transcript},
+    signature::Signature,
+    transcript::{Message, PoseidonTranscript, Transcript, poseidon_transcript},
 };
 
+/// Domain separator absorbed before hashing a nonce commitment ("commit" in ASCII).
+const NONCE_COMMITMENT_TAG: u64 = 0x636f6d6d6974;
+
 /// A MuSig aggregated signature consisting of the aggregated nonce and signature scalar.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
 pub struct MuSig<F: Field, G: CurveGroup> {
     pub agg_R: G,
     pub agg_s: F,
 }
 
+/// A Poseidon hash `H(R)` binding a signer to its nonce before any nonce is revealed.
+///
+/// In the first MuSig round every signer broadcasts only this commitment; the
+/// nonces `R_i` are revealed once all commitments have been received, which
+/// prevents a signer from choosing its nonce as a function of the others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct NonceCommitment<F: Field> {
+    pub hash: F,
+}
+
+impl<F: Field> NonceCommitment<F> {
+    /// Commits to the public nonce `R`.
+    pub fn commit<G: CurveGroup<ScalarField = F>>(R: G) -> Result<Self, SchnorrError> {
+        let mut commitment_transcript = poseidon_transcript::<F, G>();
+        commitment_transcript.absorb_scalar(F::from(NONCE_COMMITMENT_TAG));
+        commitment_transcript
+            .absorb_point(R)
+            .map_err(|_| SchnorrError::MalformedR)?;
+
+        Ok(NonceCommitment {
+            hash: commitment_transcript.squeeze_challenge(),
+        })
+    }
+
+    /// Checks that a revealed nonce `R` opens this commitment.
+    pub fn verify<G: CurveGroup<ScalarField = F>>(&self, R: G) -> bool {
+        Self::commit(R).is_ok_and(|commitment| commitment == *self)
+    }
+}
+
 impl<F: Field, G: CurveGroup<ScalarField = F>> MuSig<F, G> {
+    /// Encodes the signature as compressed `agg_R || agg_s`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encoding::to_bytes(self)
+    }
+
+    /// Decodes a signature produced by `to_bytes`, validating `agg_R` and `agg_s`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SchnorrError> {
+        Ok(encoding::from_bytes(bytes)?)
+    }
+
     /// Computes the keyset challenge from all public keys.
-    pub fn keyset_challenge(pub_keys: &[G]) -> F {
+    pub fn keyset_challenge(pub_keys: &[G]) -> Result<F, SchnorrError> {
         let mut keyset_transcript = poseidon_transcript();
 
         for pub_key in pub_keys {
-            keyset_transcript.absorb_point(*pub_key);
+            keyset_transcript.absorb_point(*pub_key)?;
         }
-        keyset_transcript.squeeze_challenge()
+        Ok(keyset_transcript.squeeze_challenge())
     }
 
     /// Aggregates public keys weighted by their keyset challenges.
-    pub fn agg_pub_keys(pub_keys: &[G], keyset_challenge: F) -> G {
+    pub fn agg_pub_keys(pub_keys: &[G], keyset_challenge: F) -> Result<G, SchnorrError> {
         let mut agg_pub_key = G::zero();
 
         for pub_key in pub_keys {
-            let mut coeff_transcript = poseidon_transcript();
-            coeff_transcript.absorb_scalar(keyset_challenge);
-            coeff_transcript.absorb_point(*pub_key);
-            let challenge = coeff_transcript.squeeze_challenge();
+            let challenge = Self::key_coefficient(keyset_challenge, *pub_key)?;
 
             agg_pub_key += (*pub_key) * challenge;
         }
 
-        agg_pub_key
+        Ok(agg_pub_key)
+    }
+
+    /// Computes the key-aggregation coefficient `H(keyset_challenge, pub_key)`.
+    pub fn key_coefficient(keyset_challenge: F, pub_key: G) -> Result<F, SchnorrError> {
+        let mut coeff_transcript = poseidon_transcript();
+        coeff_transcript.absorb_scalar(keyset_challenge);
+        coeff_transcript.absorb_point(pub_key)?;
+
+        Ok(coeff_transcript.squeeze_challenge())
     }
 
     /// Creates a random nonce and its public commitment.
@@ -63,6 +121,65 @@ impl<F: Field, G: CurveGroup<ScalarField = F>> MuSig<F, G> {
         (r, R)
     }
 
+    /// Creates a random nonce, its public commitment `R` and the hash commitment to `R`.
+    ///
+    /// Only the `NonceCommitment` is shared in the first round; `R` is revealed
+    /// after all commitments have been collected.
+    pub fn create_committed_nonce() -> Result<(F, G, NonceCommitment<F>), SchnorrError> {
+        let (r, R) = Self::create_nonce();
+        let commitment = NonceCommitment::commit(R)?;
+
+        Ok((r, R, commitment))
+    }
+
+    /// Checks every revealed nonce against its commitment and aggregates them.
+    ///
+    /// Fails with `NonceCommitmentMismatch` listing every participant whose
+    /// revealed `R_i` does not open their commitment.
+    pub fn agg_revealed_nonces(
+        commitments: &[NonceCommitment<F>],
+        revealed: &[G],
+    ) -> Result<G, SchnorrError> {
+        if commitments.len() != revealed.len() {
+            return Err(SchnorrError::LengthMismatch {
+                expected: commitments.len(),
+                found: revealed.len(),
+            });
+        }
+
+        let mismatched: Vec<usize> = commitments
+            .iter()
+            .zip(revealed)
+            .enumerate()
+            .filter(|(_, (commitment, R))| !commitment.verify(**R))
+            .map(|(index, _)| index)
+            .collect();
+        if !mismatched.is_empty() {
+            return Err(SchnorrError::NonceCommitmentMismatch(mismatched));
+        }
+
+        Ok(revealed.iter().copied().sum())
+    }
+
+    /// Signs a message after checking all revealed nonces against their commitments.
+    ///
+    /// Refuses to produce a partial signature unless `agg_revealed_nonces`
+    /// succeeds, so the secret nonce `r` is never used in a session where a
+    /// participant changed its nonce after seeing the others.
+    pub fn sign_committed(
+        keypair: Keypair<F, G>,
+        message: F,
+        keyset_challenge: F,
+        agg_pub_key: G,
+        commitments: &[NonceCommitment<F>],
+        revealed: &[G],
+        r: F,
+    ) -> Result<F, SchnorrError> {
+        let agg_R = Self::agg_revealed_nonces(commitments, revealed)?;
+
+        Self::sign(keypair, message, keyset_challenge, agg_pub_key, agg_R, r)
+    }
+
     /// Signs a message using the keypair and nonces.
     pub fn sign(
         keypair: Keypair<F, G>,
@@ -71,40 +188,227 @@ impl<F: Field, G: CurveGroup<ScalarField = F>> MuSig<F, G> {
         agg_pub_key: G,
         agg_R: G,
         r: F,
-    ) -> F {
-        let mut transcript = poseidon_transcript();
-        transcript.absorb_point(agg_pub_key);
-        transcript.absorb_point(agg_R);
-        transcript.absorb_scalar(message);
-        let challenge = transcript.squeeze_challenge();
+    ) -> Result<F, SchnorrError> {
+        Self::sign_message(
+            keypair,
+            Message::Scalar(message),
+            keyset_challenge,
+            agg_pub_key,
+            agg_R,
+            r,
+        )
+    }
 
-        let mut coeff_transcript = poseidon_transcript();
-        coeff_transcript.absorb_scalar(keyset_challenge);
-        coeff_transcript.absorb_point(keypair.public_key);
-        let coeff_challenge = coeff_transcript.squeeze_challenge();
+    /// Signs an arbitrary-length byte string using the keypair and nonces.
+    pub fn sign_bytes(
+        keypair: Keypair<F, G>,
+        message: &[u8],
+        keyset_challenge: F,
+        agg_pub_key: G,
+        agg_R: G,
+        r: F,
+    ) -> Result<F, SchnorrError> {
+        Self::sign_message(
+            keypair,
+            Message::Bytes(message),
+            keyset_challenge,
+            agg_pub_key,
+            agg_R,
+            r,
+        )
+    }
+
+    /// Signs a slice of field elements using the keypair and nonces.
+    pub fn sign_scalars(
+        keypair: Keypair<F, G>,
+        message: &[F],
+        keyset_challenge: F,
+        agg_pub_key: G,
+        agg_R: G,
+        r: F,
+    ) -> Result<F, SchnorrError> {
+        Self::sign_message(
+            keypair,
+            Message::Scalars(message),
+            keyset_challenge,
+            agg_pub_key,
+            agg_R,
+            r,
+        )
+    }
+
+    /// Signs a message in any encoding with coefficients looked up in a
+    /// precomputed `KeyAggContext`.
+    ///
+    /// The signature is for the context's possibly tweaked aggregate key; the
+    /// sign accumulator is applied here and the tweak itself in `aggregate`.
+    /// Fails with `SignerNotInKeySet` if the keypair is not part of the group.
+    pub fn sign_with_context(
+        keypair: &Keypair<F, G>,
+        key_agg: &KeyAggContext<F, G>,
+        message: Message<'_, F>,
+        agg_R: G,
+        r: F,
+    ) -> Result<F, SchnorrError> {
+        let coeff_challenge = key_agg.coefficient(&keypair.public_key)? * key_agg.gacc();
+        let challenge = Self::challenge(key_agg.agg_pub_key(), agg_R, message)?;
+
+        Ok(r + (challenge * coeff_challenge * keypair.private_key))
+    }
+
+    /// Computes a partial signature over a message in any encoding.
+    fn sign_message(
+        keypair: Keypair<F, G>,
+        message: Message<'_, F>,
+        keyset_challenge: F,
+        agg_pub_key: G,
+        agg_R: G,
+        r: F,
+    ) -> Result<F, SchnorrError> {
+        let challenge = Self::challenge(agg_pub_key, agg_R, message)?;
+        let coeff_challenge = Self::key_coefficient(keyset_challenge, keypair.public_key)?;
+
+        Ok(r + (challenge * coeff_challenge * keypair.private_key))
+    }
+
+    /// Verifies one signer's partial signature `s_i` for the key set in `key_agg`.
+    ///
+    /// Checks the equation `aggregate` checks for every signer, so it also
+    /// holds for tweaked aggregate keys. Fails with `SignerNotInKeySet` if
+    /// `pub_key` is not part of the group and with `EquationMismatch` if the
+    /// partial signature does not verify.
+    pub fn verify_partial(
+        partial_sig: F,
+        R: G,
+        pub_key: G,
+        key_agg: &KeyAggContext<F, G>,
+        agg_R: G,
+        message: Message<'_, F>,
+    ) -> Result<(), SchnorrError> {
+        let challenge = Self::challenge(key_agg.agg_pub_key(), agg_R, message)?;
+        let coefficient = key_agg.coefficient(&pub_key)?;
 
-        let s = r + (challenge * coeff_challenge * keypair.private_key);
+        if !Self::partial_holds(
+            partial_sig,
+            R,
+            pub_key,
+            coefficient * key_agg.gacc(),
+            challenge,
+        ) {
+            return Err(SchnorrError::EquationMismatch);
+        }
+        Ok(())
+    }
 
-        s
+    /// Checks `s_i·G == R_i + c·a_i·P_i`, with the sign accumulator folded into `a_i`.
+    fn partial_holds(partial_sig: F, R: G, pub_key: G, coefficient: F, challenge: F) -> bool {
+        G::generator() * partial_sig == R + pub_key * (challenge * coefficient)
     }
 
-    /// Verifies an aggregated MuSig signature.
+    /// Verifies every partial signature and sums them into a MuSig signature.
+    ///
+    /// `partial_sigs` and `revealed` are indexed like `key_agg.pub_keys()`. Fails
+    /// with `InvalidPartialSignatures` listing every signer whose `s_i` does not
+    /// verify, so a bad contribution is attributed instead of silently breaking `agg_s`.
+    /// The context's tweak accumulator is added so the result verifies under
+    /// `key_agg.agg_pub_key()`.
+    pub fn aggregate(
+        partial_sigs: &[F],
+        revealed: &[G],
+        key_agg: &KeyAggContext<F, G>,
+        message: Message<'_, F>,
+    ) -> Result<Self, SchnorrError> {
+        for len in [revealed.len(), key_agg.pub_keys().len()] {
+            if len != partial_sigs.len() {
+                return Err(SchnorrError::LengthMismatch {
+                    expected: partial_sigs.len(),
+                    found: len,
+                });
+            }
+        }
+
+        let agg_R: G = revealed.iter().copied().sum();
+        let challenge = Self::challenge(key_agg.agg_pub_key(), agg_R, message)?;
+        let invalid: Vec<usize> = partial_sigs
+            .iter()
+            .zip(revealed)
+            .zip(key_agg.pub_keys().iter().zip(key_agg.coefficients()))
+            .enumerate()
+            .filter(|(_, ((partial_sig, R), (pub_key, coefficient)))| {
+                !Self::partial_holds(
+                    **partial_sig,
+                    **R,
+                    **pub_key,
+                    key_agg.gacc() * **coefficient,
+                    challenge,
+                )
+            })
+            .map(|(index, _)| index)
+            .collect();
+        if !invalid.is_empty() {
+            return Err(SchnorrError::InvalidPartialSignatures(invalid));
+        }
+
+        Ok(MuSig {
+            agg_R,
+            agg_s: partial_sigs.iter().sum::<F>() + challenge * key_agg.tacc(),
+        })
+    }
+
+    /// Computes the challenge shared by all signers, in `Signature::challenge` order.
+    fn challenge(agg_pub_key: G, agg_R: G, message: Message<'_, F>) -> Result<F, SchnorrError> {
+        Signature::challenge(&mut PoseidonTranscript::new(), agg_R, agg_pub_key, message)
+    }
+
+    /// Returns the aggregated signature as a plain Schnorr `Signature`.
+    pub fn to_signature(&self) -> Signature<F, G> {
+        Signature {
+            R: self.agg_R,
+            s: self.agg_s,
+        }
+    }
+
+    /// Verifies the aggregated signature against the aggregate key and a message.
+    ///
+    /// MuSig signatures use the same challenge `H(agg_R, agg_pub_key, message)`
+    /// as `Signature`, so this is `Signature::verify` on `to_signature()`.
     pub fn verify(
         &self,
-        message: F,
+        agg_pub_key: G,
         transcript: &mut impl Transcript<F, G>,
+        message: F,
+    ) -> Result<(), SchnorrError> {
+        self.to_signature().verify(agg_pub_key, transcript, message)
+    }
+
+    /// Verifies an aggregated MuSig signature over a byte string.
+    pub fn verify_bytes(
+        &self,
         agg_pub_key: G,
-        agg_R: G,
-        agg_s: F,
-    ) -> bool {
-        transcript.absorb_point(agg_pub_key);
-        transcript.absorb_point(agg_R);
-        transcript.absorb_scalar(message);
+        transcript: &mut impl Transcript<F, G>,
+        message: &[u8],
+    ) -> Result<(), SchnorrError> {
+        self.to_signature()
+            .verify_bytes(agg_pub_key, transcript, message)
+    }
 
-        let challenge = transcript.squeeze_challenge();
-        let rhs = agg_R + (agg_pub_key * challenge);
-        let lhs = G::generator() * agg_s;
+    /// Verifies an aggregated MuSig signature over a slice of field elements.
+    pub fn verify_scalars(
+        &self,
+        agg_pub_key: G,
+        transcript: &mut impl Transcript<F, G>,
+        message: &[F],
+    ) -> Result<(), SchnorrError> {
+        self.to_signature()
+            .verify_scalars(agg_pub_key, transcript, message)
+    }
+}
 
-        lhs == rhs
+impl<F: Field, G: CurveGroup<ScalarField = F>> From<MuSig<F, G>> for Signature<F, G> {
+    fn from(musig: MuSig<F, G>) -> Self {
+        Signature {
+            R: musig.agg_R,
+            s: musig.agg_s,
+        }
     }
 }
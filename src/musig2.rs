@@ -0,0 +1,387 @@
+//! MuSig2 two-round multi-signature scheme using Poseidon transcript for Fiat–Shamir.
+//!
+//! Inspired by:
+//! - MuSig2 (Nick, Ruffing, Seurin): https://eprint.iacr.org/2020/1261.pdf
+//! - BIP-327 session flow, adapted to Poseidon transcripts over Arkworks
+//!
+//! Each signer publishes two nonce commitments `(R1_i, R2_i)` before the message
+//! is known. Once it is, everyone derives the nonce coefficient
+//! `b = H(agg_pub_key, R1, R2, message)` and the effective nonce `R = R1 + b·R2`,
+//! which removes the need for a nonce commitment round while staying secure
+//! against Wagner/ROS-style concurrent-session attacks.
+//!
+//! Key aggregation comes from a shared `KeyAggContext`, and the final
+//! signature is a plain `Signature` checked by `Signature::verify`.
+//!
+//! `Session::new_adaptor` runs the same flow for an adaptor point `T`: the
+//! challenge commits to `R + T` and `Session::aggregate_adaptor` returns a
+//! `PreSignature` that completes once `t` is known.
+
+#![allow(non_snake_case)]
+
+use std::fmt;
+
+use ark_ec::CurveGroup;
+use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use rand::rngs::OsRng;
+
+use crate::{
+    adaptor::PreSignature,
+    error::SchnorrError,
+    key_agg::KeyAggContext,
+    keypair::Keypair,
+    signature::Signature,
+    transcript::{Message, PoseidonTranscript, poseidon_transcript},
+};
+
+/// Domain separator absorbed before hashing the nonce coefficient `b` ("musig2" in ASCII).
+const NONCE_COEFF_TAG: u64 = 0x6d7573696732;
+
+/// A signer's secret nonce pair.
+///
+/// It is intentionally neither `Clone` nor serializable: `Session::partial_sign`
+/// takes it by value, so a nonce cannot be used for two signatures. Its
+/// `Debug` output omits both scalars.
+pub struct SecNonce<F: Field> {
+    r1: F,
+    r2: F,
+}
+
+impl<F: Field> fmt::Debug for SecNonce<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecNonce").finish_non_exhaustive()
+    }
+}
+
+/// A signer's public nonce pair `(R1_i, R2_i)`, shared in the first round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PubNonce<G: CurveGroup> {
+    pub R1: G,
+    pub R2: G,
+}
+
+/// The sum of all signers' public nonces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct AggNonce<G: CurveGroup> {
+    pub R1: G,
+    pub R2: G,
+}
+
+/// Creates a fresh nonce pair and its public commitments.
+///
+/// Nonces do not depend on the message, so they can be generated and shared
+/// ahead of time and bound to a message later through a `Session`.
+pub fn create_nonce<F: Field, G: CurveGroup<ScalarField = F>>() -> (SecNonce<F>, PubNonce<G>) {
+    let mut rng = OsRng;
+    let r1 = F::rand(&mut rng);
+    let r2 = F::rand(&mut rng);
+    let pub_nonce = PubNonce {
+        R1: G::generator() * r1,
+        R2: G::generator() * r2,
+    };
+
+    (SecNonce { r1, r2 }, pub_nonce)
+}
+
+impl<G: CurveGroup> AggNonce<G> {
+    /// Aggregates the public nonces of all signers.
+    pub fn aggregate(pub_nonces: &[PubNonce<G>]) -> Self {
+        pub_nonces.iter().fold(
+            AggNonce {
+                R1: G::zero(),
+                R2: G::zero(),
+            },
+            |agg, nonce| AggNonce {
+                R1: agg.R1 + nonce.R1,
+                R2: agg.R2 + nonce.R2,
+            },
+        )
+    }
+}
+
+/// A signing session binding the key set and aggregated nonce to one message.
+#[derive(Debug, Clone)]
+pub struct Session<F: Field, G: CurveGroup> {
+    key_agg: KeyAggContext<F, G>,
+    nonce_coeff: F,
+    R: G,
+    adaptor_point: G,
+    challenge: F,
+}
+
+impl<F: Field, G: CurveGroup<ScalarField = F>> Session<F, G> {
+    /// Starts a session for `message` signed by the group in `key_agg` with the
+    /// aggregated nonce.
+    pub fn new(
+        key_agg: &KeyAggContext<F, G>,
+        agg_nonce: AggNonce<G>,
+        message: Message<'_, F>,
+    ) -> Result<Self, SchnorrError> {
+        Self::start(key_agg, agg_nonce, G::zero(), message)
+    }
+
+    /// Starts a session whose result is a pre-signature for the adaptor point `T`.
+    pub fn new_adaptor(
+        key_agg: &KeyAggContext<F, G>,
+        agg_nonce: AggNonce<G>,
+        adaptor_point: G,
+        message: Message<'_, F>,
+    ) -> Result<Self, SchnorrError> {
+        if adaptor_point.is_zero() {
+            return Err(SchnorrError::IdentityPoint);
+        }
+
+        Self::start(key_agg, agg_nonce, adaptor_point, message)
+    }
+
+    /// Derives the nonce coefficient and challenge; a zero `adaptor_point` means none.
+    fn start(
+        key_agg: &KeyAggContext<F, G>,
+        agg_nonce: AggNonce<G>,
+        adaptor_point: G,
+        message: Message<'_, F>,
+    ) -> Result<Self, SchnorrError> {
+        let agg_pub_key = key_agg.agg_pub_key();
+
+        let mut coeff_transcript = poseidon_transcript();
+        coeff_transcript.absorb_scalar(F::from(NONCE_COEFF_TAG));
+        coeff_transcript.absorb_point(agg_pub_key)?;
+        coeff_transcript
+            .absorb_point(agg_nonce.R1)
+            .map_err(|_| SchnorrError::MalformedR)?;
+        coeff_transcript
+            .absorb_point(agg_nonce.R2)
+            .map_err(|_| SchnorrError::MalformedR)?;
+        if !adaptor_point.is_zero() {
+            coeff_transcript.absorb_point(adaptor_point)?;
+        }
+        coeff_transcript.absorb_message(message);
+        let nonce_coeff = coeff_transcript.squeeze_challenge();
+
+        let R = agg_nonce.R1 + agg_nonce.R2 * nonce_coeff;
+        let mut transcript = PoseidonTranscript::new();
+        let challenge =
+            Signature::challenge(&mut transcript, R + adaptor_point, agg_pub_key, message)?;
+
+        Ok(Session {
+            key_agg: key_agg.clone(),
+            nonce_coeff,
+            R,
+            adaptor_point,
+            challenge,
+        })
+    }
+
+    /// Returns the aggregated public key the final signature verifies under.
+    pub fn agg_pub_key(&self) -> G {
+        self.key_agg.agg_pub_key()
+    }
+
+    /// Returns the nonce of the final signature, `R1 + b·R2` plus any adaptor point.
+    pub fn final_nonce(&self) -> G {
+        self.R + self.adaptor_point
+    }
+
+    /// Computes the partial signature `s_i = r1 + b·r2 + c·gacc·a_i·x_i`, consuming the nonce.
+    pub fn partial_sign(
+        &self,
+        keypair: &Keypair<F, G>,
+        sec_nonce: SecNonce<F>,
+    ) -> Result<F, SchnorrError> {
+        let key_coeff = self.key_agg.coefficient(&keypair.public_key)? * self.key_agg.gacc();
+
+        Ok(sec_nonce.r1
+            + self.nonce_coeff * sec_nonce.r2
+            + self.challenge * key_coeff * keypair.private_key)
+    }
+
+    /// Sums the partial signatures into a Schnorr signature under `agg_pub_key`,
+    /// adding `c·tacc` for a tweaked key.
+    pub fn aggregate(&self, partial_sigs: &[F]) -> Signature<F, G> {
+        Signature {
+            R: self.final_nonce(),
+            s: self.sum_partial_sigs(partial_sigs),
+        }
+    }
+
+    /// Sums the partial signatures of an adaptor session into a pre-signature.
+    ///
+    /// Fails with `IdentityPoint` for a session started without an adaptor point.
+    pub fn aggregate_adaptor(
+        &self,
+        partial_sigs: &[F],
+    ) -> Result<PreSignature<F, G>, SchnorrError> {
+        if self.adaptor_point.is_zero() {
+            return Err(SchnorrError::IdentityPoint);
+        }
+
+        Ok(PreSignature {
+            R: self.R,
+            T: self.adaptor_point,
+            s: self.sum_partial_sigs(partial_sigs),
+        })
+    }
+
+    fn sum_partial_sigs(&self, partial_sigs: &[F]) -> F {
+        partial_sigs.iter().sum::<F>() + self.challenge * self.key_agg.tacc()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    type F = ark_bn254::Fr;
+    type G = ark_bn254::G1Projective;
+
+    #[test]
+    fn test_musig2_signature_valid() {
+        let keypairs: Vec<_> = (0..3).map(|_| Keypair::<F, G>::generate()).collect();
+        let pub_keys: Vec<_> = keypairs.iter().map(|k| k.public_key).collect();
+        let key_agg = KeyAggContext::new(&pub_keys).unwrap();
+
+        // Round 1 happens before the message is known.
+        let (sec_nonces, pub_nonces): (Vec<_>, Vec<_>) =
+            (0..3).map(|_| create_nonce::<F, G>()).unzip();
+        let agg_nonce = AggNonce::aggregate(&pub_nonces);
+
+        let message = F::from(16);
+        let session = Session::new(&key_agg, agg_nonce, Message::Scalar(message)).unwrap();
+
+        let partial_sigs: Vec<_> = keypairs
+            .iter()
+            .zip(sec_nonces)
+            .map(|(keypair, sec_nonce)| session.partial_sign(keypair, sec_nonce).unwrap())
+            .collect();
+        let signature = session.aggregate(&partial_sigs);
+
+        let mut transcript = PoseidonTranscript::new();
+        assert!(
+            signature
+                .verify(session.agg_pub_key(), &mut transcript, message)
+                .is_ok()
+        );
+
+        let mut transcript = PoseidonTranscript::new();
+        assert_eq!(
+            signature.verify(session.agg_pub_key(), &mut transcript, F::from(17)),
+            Err(SchnorrError::EquationMismatch)
+        );
+    }
+
+    #[test]
+    fn test_musig2_byte_message() {
+        let keypairs: Vec<_> = (0..2).map(|_| Keypair::<F, G>::generate()).collect();
+        let pub_keys: Vec<_> = keypairs.iter().map(|k| k.public_key).collect();
+        let key_agg = KeyAggContext::new(&pub_keys).unwrap();
+        let (sec_nonces, pub_nonces): (Vec<_>, Vec<_>) =
+            (0..2).map(|_| create_nonce::<F, G>()).unzip();
+
+        let message = b"transfer 5";
+        let session = Session::new(
+            &key_agg,
+            AggNonce::aggregate(&pub_nonces),
+            Message::Bytes(message),
+        )
+        .unwrap();
+        let partial_sigs: Vec<_> = keypairs
+            .iter()
+            .zip(sec_nonces)
+            .map(|(keypair, sec_nonce)| session.partial_sign(keypair, sec_nonce).unwrap())
+            .collect();
+        let signature = session.aggregate(&partial_sigs);
+
+        let mut transcript = PoseidonTranscript::new();
+        assert!(
+            signature
+                .verify_bytes(session.agg_pub_key(), &mut transcript, message)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_musig2_nonce_coefficient_binds_message() {
+        let key_agg = KeyAggContext::new(&[
+            Keypair::<F, G>::generate().public_key,
+            Keypair::<F, G>::generate().public_key,
+        ])
+        .unwrap();
+        let pub_nonces = [create_nonce::<F, G>().1, create_nonce::<F, G>().1];
+        let agg_nonce = AggNonce::aggregate(&pub_nonces);
+
+        let first = Session::new(&key_agg, agg_nonce, Message::Scalar(F::from(1))).unwrap();
+        let second = Session::new(&key_agg, agg_nonce, Message::Scalar(F::from(2))).unwrap();
+
+        assert_ne!(first.final_nonce(), second.final_nonce());
+    }
+
+    #[test]
+    fn test_musig2_adaptor_signature() {
+        let keypairs: Vec<_> = (0..3).map(|_| Keypair::<F, G>::generate()).collect();
+        let pub_keys: Vec<_> = keypairs.iter().map(|k| k.public_key).collect();
+        let key_agg = KeyAggContext::new(&pub_keys).unwrap();
+        let adaptor = Keypair::<F, G>::generate();
+        let (sec_nonces, pub_nonces): (Vec<_>, Vec<_>) =
+            (0..3).map(|_| create_nonce::<F, G>()).unzip();
+
+        let message = F::from(16);
+        let session = Session::new_adaptor(
+            &key_agg,
+            AggNonce::aggregate(&pub_nonces),
+            adaptor.public_key,
+            Message::Scalar(message),
+        )
+        .unwrap();
+        let partial_sigs: Vec<_> = keypairs
+            .iter()
+            .zip(sec_nonces)
+            .map(|(keypair, sec_nonce)| session.partial_sign(keypair, sec_nonce).unwrap())
+            .collect();
+        let pre_signature = session.aggregate_adaptor(&partial_sigs).unwrap();
+
+        let mut transcript = PoseidonTranscript::new();
+        assert!(
+            pre_signature
+                .verify(
+                    session.agg_pub_key(),
+                    &mut transcript,
+                    Message::Scalar(message)
+                )
+                .is_ok()
+        );
+
+        let signature = pre_signature.complete(adaptor.private_key).unwrap();
+        assert_eq!(signature.R, session.final_nonce());
+        let mut transcript = PoseidonTranscript::new();
+        assert!(
+            signature
+                .verify(session.agg_pub_key(), &mut transcript, message)
+                .is_ok()
+        );
+        assert_eq!(
+            pre_signature.extract(&signature).unwrap(),
+            adaptor.private_key
+        );
+    }
+
+    #[test]
+    fn test_musig2_rejects_outside_signer() {
+        let keypair = Keypair::<F, G>::generate();
+        let outsider = Keypair::<F, G>::generate();
+        let (_, pub_nonce) = create_nonce::<F, G>();
+        let (sec_nonce, _) = create_nonce::<F, G>();
+
+        let session = Session::new(
+            &KeyAggContext::new(&[keypair.public_key]).unwrap(),
+            AggNonce::aggregate(&[pub_nonce]),
+            Message::Scalar(F::from(1)),
+        )
+        .unwrap();
+
+        assert_eq!(
+            session.partial_sign(&outsider, sec_nonce),
+            Err(SchnorrError::SignerNotInKeySet)
+        );
+    }
+}
@@ -1,20 +1,58 @@
+/// Sparse partial rounds for the optimised permutation
+pub mod optimized;
 pub mod params;
+/// Native Poseidon2 permutation
+pub mod poseidon2;
 /// Native sponge implementation
 pub mod sponge;
-use self::params::hasher::{RoundParams, Sbox, poseidon_bn254_5x5::Params};
+use self::{
+    params::hasher::{Poseidon2Params, RoundParams, poseidon_bn254_5x5::Params},
+    poseidon2::Poseidon2,
+};
 use ark_ff::Field;
+use std::{fmt::Debug, marker::PhantomData};
+
+/// A permutation of `WIDTH` field elements, used to select the permutation
+/// behind `PoseidonSponge` and `PoseidonTranscript`.
+pub trait Permutation<F: Field, const WIDTH: usize>: Clone + Debug {
+    /// Permutes the state.
+    fn apply(state: [F; WIDTH]) -> [F; WIDTH];
+}
+
+impl<F: Field, const WIDTH: usize, P: RoundParams<F, WIDTH>> Permutation<F, WIDTH>
+    for Poseidon<F, WIDTH, P>
+{
+    fn apply(state: [F; WIDTH]) -> [F; WIDTH] {
+        Self::new(state).permute_optimized()
+    }
+}
+
+impl<F: Field, const WIDTH: usize, P: Poseidon2Params<F, WIDTH>> Permutation<F, WIDTH>
+    for Poseidon2<F, WIDTH, P>
+{
+    fn apply(state: [F; WIDTH]) -> [F; WIDTH] {
+        Self::new(state).permute()
+    }
+}
 
 /// Constructs objects.
+///
+/// `WIDTH` is the state size and `P` the matching parameter set; the
+/// defaults are the BN254 width-5 parameters used by the sponge.
 #[derive(Debug, Clone)]
-pub struct Poseidon<F: Field> {
+pub struct Poseidon<F: Field, const WIDTH: usize = 5, P: RoundParams<F, WIDTH> = Params<F>> {
     /// Constructs an array for the inputs.
-    inputs: [F; 5],
+    inputs: [F; WIDTH],
+    _params: PhantomData<P>,
 }
 
-impl<F: Field> Poseidon<F> {
+impl<F: Field, const WIDTH: usize, P: RoundParams<F, WIDTH>> Poseidon<F, WIDTH, P> {
     /// Create the objects.
-    pub fn new(inputs: [F; 5]) -> Self {
-        Poseidon { inputs }
+    pub fn new(inputs: [F; WIDTH]) -> Self {
+        Poseidon {
+            inputs,
+            _params: PhantomData,
+        }
     }
 
     /// The Hades Design Strategy for Hashing.
@@ -23,66 +61,101 @@ impl<F: Field> Poseidon<F> {
     /// More detailed explanation for
     /// The Round Function (TRF) and Hades:
     /// https://eprint.iacr.org/2019/458.pdf#page=5
-    pub fn permute(&self) -> [F; 5] {
-        let full_rounds = Params::<F>::full_rounds();
-        let half_full_rounds = full_rounds / 2;
-        let partial_rounds = Params::<F>::partial_rounds();
-        let round_constants = Params::<F>::round_constants();
-        let total_count = Params::<F>::round_constants_count();
-
-        let first_round_end = half_full_rounds * 5;
+    pub fn permute(&self) -> [F; WIDTH] {
+        let config = P::config();
+        let half_full_rounds = config.full_rounds / 2;
+        let partial_rounds = config.partial_rounds;
+        let round_constants = &config.round_constants;
+        let total_count = round_constants.len();
+
+        let first_round_end = half_full_rounds * WIDTH;
         let first_round_constants = &round_constants[0..first_round_end];
 
-        let second_round_end = first_round_end + partial_rounds * 5;
+        let second_round_end = first_round_end + partial_rounds * WIDTH;
         let second_round_constants = &round_constants[first_round_end..second_round_end];
 
         let third_round_constants = &round_constants[second_round_end..total_count];
 
         let mut state = self.inputs;
         for round in 0..half_full_rounds {
-            let round_consts = Params::<F>::load_round_constants(round, first_round_constants);
+            let round_consts = P::load_round_constants(round, first_round_constants);
             // 1. step for the TRF.
             // AddRoundConstants step.
-            state = Params::<F>::apply_round_constants(&state, &round_consts);
+            state = P::apply_round_constants(&state, &round_consts);
             // Applying S-boxes for the full round.
-            for state in state.iter_mut().take(5) {
+            for state in state.iter_mut().take(WIDTH) {
                 // 2. step for the TRF.
                 // SubWords step.
-                *state = Params::<F>::sbox_f(*state);
+                *state = P::sbox_f(*state);
             }
             // 3. step for the TRF.
             // MixLayer step.
-            state = Params::<F>::apply_mds(&state);
+            state = config.apply_mds(&state);
         }
 
         for round in 0..partial_rounds {
-            let round_consts = Params::<F>::load_round_constants(round, second_round_constants);
+            let round_consts = P::load_round_constants(round, second_round_constants);
             // 1. step for the TRF.
             // AddRoundConstants step.
-            state = Params::<F>::apply_round_constants(&state, &round_consts);
+            state = P::apply_round_constants(&state, &round_consts);
             // Applying single S-box for the partial round.
             // 2. step for the TRF.
             // SubWords step, denoted by S-box.
-            state[0] = Params::<F>::sbox_f(state[0]);
+            state[0] = P::sbox_f(state[0]);
             // 3. step for the TRF.
             // MixLayer step.
-            state = Params::<F>::apply_mds(&state);
+            state = config.apply_mds(&state);
         }
 
         for round in 0..half_full_rounds {
-            let round_consts = Params::<F>::load_round_constants(round, third_round_constants);
+            let round_consts = P::load_round_constants(round, third_round_constants);
             // 1. step for the TRF.
             // AddRoundConstants step.
-            state = Params::<F>::apply_round_constants(&state, &round_consts);
+            state = P::apply_round_constants(&state, &round_consts);
             // Applying S-boxes for the full round.
-            for state in state.iter_mut().take(5) {
+            for state in state.iter_mut().take(WIDTH) {
                 // 2. step for the TRF.
                 // SubWords step, denoted by S-box.
-                *state = Params::<F>::sbox_f(*state);
+                *state = P::sbox_f(*state);
             }
             // 3. step for the TRF.
             // MixLayer step.
-            state = Params::<F>::apply_mds(&state);
+            state = config.apply_mds(&state);
+        }
+
+        state
+    }
+
+    /// Computes the same output as `permute` with sparse partial rounds.
+    ///
+    /// Partial rounds add one constant and multiply by a sparse matrix in
+    /// `O(WIDTH)` instead of a dense MDS multiply; see `optimized` for how the
+    /// equivalent constants and matrices are derived.
+    pub fn permute_optimized(&self) -> [F; WIDTH] {
+        let config = P::config();
+        let rounds = &config.optimized;
+        let (first_round_constants, third_round_constants) =
+            rounds.full_round_constants.split_at(config.full_rounds / 2);
+
+        let mut state = self.inputs;
+        for round_consts in first_round_constants {
+            state = P::apply_round_constants(&state, round_consts).map(P::sbox_f);
+            state = config.apply_mds(&state);
+        }
+
+        for (round_const, matrix) in rounds
+            .partial_round_constants
+            .iter()
+            .zip(&rounds.sparse_matrices)
+        {
+            state[0] = P::sbox_f(state[0] + round_const);
+            state = matrix.apply(&state);
+        }
+        state = optimized::mat_vec(&rounds.final_matrix, &state);
+
+        for round_consts in third_round_constants {
+            state = P::apply_round_constants(&state, round_consts).map(P::sbox_f);
+            state = config.apply_mds(&state);
         }
 
         state
@@ -92,8 +165,13 @@ impl<F: Field> Poseidon<F> {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::poseidon_hash::params::hasher::hex_to_field;
+    use crate::error::SchnorrError;
+    use crate::poseidon_hash::params::hasher::{
+        hex_to_field, poseidon_bn254_3x3, try_hex_to_field,
+    };
     use ark_bn254::Fr;
+    use ark_ff::UniformRand;
+    use rand::rngs::OsRng;
 
     type TestPoseidon = Poseidon<Fr>;
 
@@ -107,7 +185,7 @@ mod test {
             "0x0000000000000000000000000000000000000000000000000000000000000003",
             "0x0000000000000000000000000000000000000000000000000000000000000004",
         ]
-        .map(|n| hex_to_field(n));
+        .map(hex_to_field);
 
         let outputs: [Fr; 5] = [
             "0x299c867db6c1fdd79dcefa40e4510b9837e60ebb1ce0663dbaa525df65250465",
@@ -116,12 +194,70 @@ mod test {
             "0x0eb08f6d809668a981c186beaf6110060707059576406b248e5d9cf6e78b3d3e",
             "0x07748bc6877c9b82c8b98666ee9d0626ec7f5be4205f79ee8528ef1c4a376fc7",
         ]
-        .map(|n| hex_to_field(n));
+        .map(hex_to_field);
 
         let poseidon = TestPoseidon::new(inputs);
 
         let out = poseidon.permute();
 
         assert_eq!(out, outputs);
+        assert_eq!(poseidon.permute_optimized(), outputs);
+    }
+
+    #[test]
+    fn test_native_poseidon_3x3() {
+        // Testing 3x3 input against circomlib's t = 3 parameters.
+        let inputs: [Fr; 3] = [
+            "0x0000000000000000000000000000000000000000000000000000000000000000",
+            "0x0000000000000000000000000000000000000000000000000000000000000001",
+            "0x0000000000000000000000000000000000000000000000000000000000000002",
+        ]
+        .map(hex_to_field);
+
+        let poseidon = Poseidon::<Fr, 3, poseidon_bn254_3x3::Params<Fr>>::new(inputs);
+
+        let out = poseidon.permute();
+
+        assert_eq!(
+            out[0],
+            hex_to_field("0x115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a")
+        );
+    }
+
+    #[test]
+    fn test_optimized_permute_matches_reference() {
+        let mut rng = OsRng;
+        for _ in 0..4 {
+            let poseidon = TestPoseidon::new(std::array::from_fn(|_| Fr::rand(&mut rng)));
+            assert_eq!(poseidon.permute_optimized(), poseidon.permute());
+
+            let poseidon =
+                Poseidon::<Fr, 3, poseidon_bn254_3x3::Params<Fr>>::new(std::array::from_fn(|_| {
+                    Fr::rand(&mut rng)
+                }));
+            assert_eq!(poseidon.permute_optimized(), poseidon.permute());
+        }
+    }
+
+    #[test]
+    fn test_try_hex_to_field() {
+        assert_eq!(try_hex_to_field::<Fr>("0x10"), Ok(Fr::from(16)));
+        assert_eq!(try_hex_to_field::<Fr>("10"), Err(SchnorrError::InvalidHex));
+        assert_eq!(
+            try_hex_to_field::<Fr>("0xzz"),
+            Err(SchnorrError::InvalidHex)
+        );
+        // The scalar field modulus itself is not canonical.
+        assert_eq!(
+            try_hex_to_field::<Fr>(
+                "0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001"
+            ),
+            Err(SchnorrError::InvalidHex)
+        );
+        // Longer than a field element.
+        assert_eq!(
+            try_hex_to_field::<Fr>(&format!("0x{}", "00".repeat(33))),
+            Err(SchnorrError::InvalidHex)
+        );
     }
 }
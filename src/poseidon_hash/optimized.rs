@@ -0,0 +1,202 @@
+//! Precomputation for the optimised Poseidon partial rounds.
+//!
+//! Inspired by:
+//! - Poseidon, Appendix B: https://eprint.iacr.org/2019/458.pdf
+//! - Neptune, the Poseidon implementation used by Filecoin
+//!
+//! A partial round maps `x` to `M·S0(x + c)`, where `S0` only applies the
+//! S-box to the first element. Two rewrites keep the output identical:
+//!
+//! 1. Only `c[0]` has to be added before `S0`; the rest of `c` passes through
+//!    it unchanged, so `M·(0, c[1..])` is added to the next round's constants
+//!    instead. Every partial round is left with a single scalar constant.
+//! 2. Write `M = P·S` with `P = diag(1, M̂)` and `S` the identity apart from
+//!    its first row and column. `P` commutes with `S0` and with adding to the
+//!    first element, so it moves into the next round, whose matrix `M·P` is
+//!    factored the same way. Each partial round then only multiplies by a
+//!    sparse `S_i` in `O(t)`, and the leftover `P` is applied once at the end.
+
+use ark_ff::Field;
+
+/// A matrix that equals the identity except for its first row and column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseMatrix<F: Field, const WIDTH: usize> {
+    /// The full first row.
+    row: [F; WIDTH],
+    /// The first column below the diagonal; `column[0]` is unused.
+    column: [F; WIDTH],
+}
+
+impl<F: Field, const WIDTH: usize> SparseMatrix<F, WIDTH> {
+    /// Multiplies the state by the matrix in `O(WIDTH)`.
+    pub fn apply(&self, state: &[F; WIDTH]) -> [F; WIDTH] {
+        let first = dot(&self.row, state);
+        std::array::from_fn(|i| {
+            if i == 0 {
+                first
+            } else {
+                state[i] + self.column[i] * state[0]
+            }
+        })
+    }
+}
+
+/// Round constants and matrices for the optimised permutation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OptimizedRounds<F: Field, const WIDTH: usize> {
+    /// Constants of the full rounds, both halves in order.
+    pub(crate) full_round_constants: Vec<[F; WIDTH]>,
+    /// The single constant added to the first element in each partial round.
+    pub(crate) partial_round_constants: Vec<F>,
+    /// The sparse matrix of each partial round.
+    pub(crate) sparse_matrices: Vec<SparseMatrix<F, WIDTH>>,
+    /// The dense matrix applied once after the last partial round.
+    pub(crate) final_matrix: [[F; WIDTH]; WIDTH],
+}
+
+impl<F: Field, const WIDTH: usize> OptimizedRounds<F, WIDTH> {
+    /// Derives the optimised rounds from the reference constants and MDS matrix.
+    pub fn new(
+        full_rounds: usize,
+        partial_rounds: usize,
+        round_constants: &[F],
+        mds: &[[F; WIDTH]; WIDTH],
+    ) -> Self {
+        let half_full_rounds = full_rounds / 2;
+        let partial_end = half_full_rounds + partial_rounds;
+        let mut constants: Vec<[F; WIDTH]> = round_constants
+            .chunks(WIDTH)
+            .map(|chunk| std::array::from_fn(|i| chunk[i]))
+            .collect();
+
+        let mut partial_round_constants = Vec::with_capacity(partial_rounds);
+        for round in half_full_rounds..partial_end {
+            let mut moved = constants[round];
+            partial_round_constants.push(moved[0]);
+            moved[0] = F::ZERO;
+            let pushed = mat_vec(mds, &moved);
+            for (constant, pushed) in constants[round + 1].iter_mut().zip(pushed) {
+                *constant += pushed;
+            }
+        }
+
+        let mut dense = identity();
+        let mut sparse_matrices = Vec::with_capacity(partial_rounds);
+        for _ in 0..partial_rounds {
+            let (sparse, block) = factor(&mat_mul(mds, &dense));
+            sparse_matrices.push(sparse);
+            dense = block;
+        }
+
+        let full_round_constants = constants[..half_full_rounds]
+            .iter()
+            .chain(&constants[partial_end..])
+            .copied()
+            .collect();
+
+        OptimizedRounds {
+            full_round_constants,
+            partial_round_constants,
+            sparse_matrices,
+            final_matrix: dense,
+        }
+    }
+}
+
+fn dot<F: Field, const WIDTH: usize>(a: &[F; WIDTH], b: &[F; WIDTH]) -> F {
+    a.iter().zip(b).fold(F::ZERO, |sum, (a, b)| sum + *a * b)
+}
+
+/// Multiplies a square matrix by a column vector.
+pub(crate) fn mat_vec<F: Field, const WIDTH: usize>(
+    matrix: &[[F; WIDTH]; WIDTH],
+    vector: &[F; WIDTH],
+) -> [F; WIDTH] {
+    matrix.map(|row| dot(&row, vector))
+}
+
+fn mat_mul<F: Field, const WIDTH: usize>(
+    a: &[[F; WIDTH]; WIDTH],
+    b: &[[F; WIDTH]; WIDTH],
+) -> [[F; WIDTH]; WIDTH] {
+    a.map(|row| std::array::from_fn(|j| (0..WIDTH).fold(F::ZERO, |sum, k| sum + row[k] * b[k][j])))
+}
+
+fn identity<F: Field, const WIDTH: usize>() -> [[F; WIDTH]; WIDTH] {
+    std::array::from_fn(|i| std::array::from_fn(|j| if i == j { F::ONE } else { F::ZERO }))
+}
+
+/// Splits `matrix` into `diag(1, M̂)·S`, returning `S` and `diag(1, M̂)`.
+fn factor<F: Field, const WIDTH: usize>(
+    matrix: &[[F; WIDTH]; WIDTH],
+) -> (SparseMatrix<F, WIDTH>, [[F; WIDTH]; WIDTH]) {
+    let block: Vec<Vec<F>> = matrix[1..].iter().map(|row| row[1..].to_vec()).collect();
+    let inverse = invert(block);
+    let column = std::array::from_fn(|i| {
+        if i == 0 {
+            F::ZERO
+        } else {
+            inverse[i - 1]
+                .iter()
+                .zip(&matrix[1..])
+                .fold(F::ZERO, |sum, (inv, row)| sum + *inv * row[0])
+        }
+    });
+    let dense = std::array::from_fn(|i| {
+        std::array::from_fn(|j| match (i, j) {
+            (0, 0) => F::ONE,
+            (0, _) | (_, 0) => F::ZERO,
+            _ => matrix[i][j],
+        })
+    });
+
+    (
+        SparseMatrix {
+            row: matrix[0],
+            column,
+        },
+        dense,
+    )
+}
+
+/// Inverts a square matrix by Gauss–Jordan elimination.
+///
+/// Only called on lower-right blocks of products of MDS matrices with
+/// invertible block-diagonal matrices, which are always invertible.
+fn invert<F: Field>(mut matrix: Vec<Vec<F>>) -> Vec<Vec<F>> {
+    let n = matrix.len();
+    let mut inverse: Vec<Vec<F>> = (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| if i == j { F::ONE } else { F::ZERO })
+                .collect()
+        })
+        .collect();
+
+    for col in 0..n {
+        let pivot = (col..n)
+            .find(|row| !matrix[*row][col].is_zero())
+            .expect("MDS submatrices are invertible");
+        matrix.swap(col, pivot);
+        inverse.swap(col, pivot);
+
+        let scale = matrix[col][col].inverse().expect("pivot is non-zero");
+        for j in 0..n {
+            matrix[col][j] *= scale;
+            inverse[col][j] *= scale;
+        }
+        for row in 0..n {
+            let factor = matrix[row][col];
+            if row == col || factor.is_zero() {
+                continue;
+            }
+            for j in 0..n {
+                let (pivot_value, pivot_inverse) = (matrix[col][j], inverse[col][j]);
+                matrix[row][j] -= factor * pivot_value;
+                inverse[row][j] -= factor * pivot_inverse;
+            }
+        }
+    }
+
+    inverse
+}
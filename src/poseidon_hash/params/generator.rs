@@ -0,0 +1,539 @@
+//! Poseidon parameter generation following the reference Sage script.
+//!
+//! Inspired by:
+//! - Grassi et al., "Poseidon": https://eprint.iacr.org/2019/458.pdf
+//! - `generate_params_poseidon.sage` and `calc_round_numbers.py` from the
+//!   Poseidon reference implementation
+//! - Grassi, Rechberger, Schofnegger, "Proving Resistance Against Infinitely
+//!   Long Subspace Trails": https://eprint.iacr.org/2020/500.pdf
+//!
+//! A Grain LFSR is seeded with the field type, S-box, field size, width and
+//! round numbers. Round constants are rejection-sampled from its output, and
+//! the MDS matrix is the Cauchy matrix `1 / (x_i + y_j)` over the next `2t`
+//! draws, redrawn until it passes the invariant-subspace checks.
+//!
+//! `to_rust_source` prints the result as a `RoundParams` implementation in the
+//! layout of `poseidon_bn254_5x5.rs`, which it reproduces byte for byte.
+
+use std::fmt::Write;
+
+use ark_ff::{BigInteger, PrimeField};
+
+use crate::error::SchnorrError;
+
+/// Security margin applied by `round_numbers`: two more full rounds and 7.5%
+/// more partial rounds.
+const FULL_ROUNDS_MARGIN: usize = 2;
+const PARTIAL_ROUNDS_MARGIN: f64 = 1.075;
+
+/// Grain LFSR in self-shrinking mode, as specified in the Poseidon paper.
+struct Grain {
+    state: [bool; 80],
+    position: usize,
+}
+
+impl Grain {
+    /// Seeds the LFSR and discards the first 160 output bits.
+    fn new(field_bits: usize, width: usize, full_rounds: usize, partial_rounds: usize) -> Self {
+        let mut state = [true; 80];
+        let fields: [(usize, usize); 6] = [
+            (1, 2), // prime field
+            (0, 4), // S-box x^alpha
+            (field_bits, 12),
+            (width, 12),
+            (full_rounds, 10),
+            (partial_rounds, 10),
+        ];
+        let mut position = 0;
+        for (value, bits) in fields {
+            for bit in (0..bits).rev() {
+                state[position] = (value >> bit) & 1 == 1;
+                position += 1;
+            }
+        }
+
+        let mut grain = Grain { state, position: 0 };
+        for _ in 0..160 {
+            grain.step();
+        }
+        grain
+    }
+
+    /// Shifts the register by one and returns the new bit.
+    fn step(&mut self) -> bool {
+        let bit = |offset: usize| self.state[(self.position + offset) % 80];
+        let new_bit = bit(62) ^ bit(51) ^ bit(38) ^ bit(23) ^ bit(13) ^ bit(0);
+        self.state[self.position] = new_bit;
+        self.position = (self.position + 1) % 80;
+        new_bit
+    }
+
+    /// Returns the next output bit: of each pair, the second is kept if the first is set.
+    fn next_bit(&mut self) -> bool {
+        loop {
+            if self.step() {
+                return self.step();
+            }
+            self.step();
+        }
+    }
+
+    /// Returns the next `count` output bits, most significant first.
+    fn next_bits(&mut self, count: usize) -> Vec<bool> {
+        (0..count).map(|_| self.next_bit()).collect()
+    }
+
+    /// Samples a field element by rejecting values not below the modulus.
+    fn next_field_element<F: PrimeField>(&mut self) -> F {
+        loop {
+            let bits = self.next_bits(F::MODULUS_BIT_SIZE as usize);
+            if let Some(element) = F::from_bigint(F::BigInt::from_bits_be(&bits)) {
+                return element;
+            }
+        }
+    }
+
+    /// Samples a field element by reducing modulo the field size.
+    fn next_reduced_element<F: PrimeField>(&mut self) -> F {
+        let bits = self.next_bits(F::MODULUS_BIT_SIZE as usize);
+        F::from_be_bytes_mod_order(&F::BigInt::from_bits_be(&bits).to_bytes_be())
+    }
+}
+
+/// Poseidon parameters generated for one field, width and S-box.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneratedParams<F: PrimeField> {
+    pub width: usize,
+    pub alpha: u64,
+    pub full_rounds: usize,
+    pub partial_rounds: usize,
+    pub round_constants: Vec<F>,
+    pub mds: Vec<Vec<F>>,
+}
+
+impl<F: PrimeField> GeneratedParams<F> {
+    /// Generates the round constants and a secure MDS matrix for the given
+    /// round numbers, e.g. from `round_numbers`.
+    ///
+    /// Fails with `InvalidPoseidonParameters` if the width is below 2, the
+    /// S-box `x^alpha` is not a permutation of `F`, the number of full rounds
+    /// is odd, or a value does not fit the LFSR seed.
+    pub fn new(
+        width: usize,
+        alpha: u64,
+        full_rounds: usize,
+        partial_rounds: usize,
+    ) -> Result<Self, SchnorrError> {
+        let field_bits = F::MODULUS_BIT_SIZE as usize;
+        if !(2..1 << 12).contains(&width)
+            || field_bits >= 1 << 12
+            || !full_rounds.is_multiple_of(2)
+            || full_rounds >= 1 << 10
+            || partial_rounds >= 1 << 10
+            || alpha_inverse::<F>(alpha).is_none()
+        {
+            return Err(SchnorrError::InvalidPoseidonParameters);
+        }
+
+        let mut grain = Grain::new(field_bits, width, full_rounds, partial_rounds);
+        let round_constants = (0..(full_rounds + partial_rounds) * width)
+            .map(|_| grain.next_field_element())
+            .collect();
+        let mds = loop {
+            if let Some(mds) = cauchy_matrix(&mut grain, width)
+                && is_secure_mds(&mds)
+            {
+                break mds;
+            }
+        };
+
+        Ok(GeneratedParams {
+            width,
+            alpha,
+            full_rounds,
+            partial_rounds,
+            round_constants,
+            mds,
+        })
+    }
+
+    /// Renders the parameters as a parameter file for `params::hasher`.
+    pub fn to_rust_source(&self) -> String {
+        let width = self.width;
+        let alpha = self.alpha;
+        let sbox = if alpha == 5 {
+            "        let f2 = f * f;\n        let f4 = f2 * f2;\n        f4 * f\n".to_string()
+        } else {
+            format!("        f.pow([{alpha}])\n")
+        };
+        let inverse_limbs: String = alpha_inverse::<F>(alpha)
+            .expect("checked when the parameters were generated")
+            .iter()
+            .map(|limb| format!("            {limb},\n"))
+            .collect();
+
+        let mut source = format!(
+            "use std::marker::PhantomData;
+
+use super::*;
+use ark_ff::Field;
+#[derive(Clone, Debug)]
+/// Configures a structure.
+pub struct Params<F: Field> {{
+    _marker: PhantomData<F>,
+}}
+
+impl<F: Field> Sbox<F> for Params<F> {{
+    fn sbox_f(f: F) -> F {{
+{sbox}    }}
+
+    fn sbox_inv_f(f: F) -> F {{
+        // Pow by inverse of {alpha}
+        f.pow([
+{inverse_limbs}        ])
+    }}
+}}
+
+impl<F: Field> RoundParams<F, {width}> for Params<F> {{
+    fn partial_rounds() -> usize {{
+        {}
+    }}
+
+    fn full_rounds() -> usize {{
+        {}
+    }}
+
+    fn round_constants_raw() -> Vec<&'static str> {{
+        [
+",
+            self.partial_rounds, self.full_rounds
+        );
+        for constant in &self.round_constants {
+            writeln!(source, "            \"{}\",", to_hex(constant)).expect("writing to a String");
+        }
+        write!(
+            source,
+            "        ]
+        .to_vec()
+    }}
+
+    fn mds_raw() -> [[&'static str; {width}]; {width}] {{
+        [
+"
+        )
+        .expect("writing to a String");
+        for row in &self.mds {
+            source.push_str("            [\n");
+            for entry in row {
+                writeln!(source, "                \"{}\",", to_hex(entry))
+                    .expect("writing to a String");
+            }
+            source.push_str("            ],\n");
+        }
+        source.push_str("        ]\n    }\n}\n");
+
+        source
+    }
+}
+
+/// Formats a field element as `0x`-prefixed big-endian hex.
+fn to_hex<F: PrimeField>(element: &F) -> String {
+    format!("0x{}", hex::encode(element.into_bigint().to_bytes_be()))
+}
+
+/// Returns `alpha⁻¹ mod (p - 1)` as little-endian limbs if `x^alpha` is a permutation.
+fn alpha_inverse<F: PrimeField>(alpha: u64) -> Option<Vec<u64>> {
+    if alpha < 3 {
+        return None;
+    }
+    let mut order: Vec<u64> = F::MODULUS.as_ref().to_vec();
+    order[0] -= 1;
+
+    // alpha·d = k·(p - 1) + 1 for some 0 < k < alpha.
+    (1..alpha).find_map(|k| {
+        let mut carry = 1u128;
+        let mut numerator: Vec<u64> = order
+            .iter()
+            .map(|limb| {
+                let value = *limb as u128 * k as u128 + carry;
+                carry = value >> 64;
+                value as u64
+            })
+            .collect();
+        numerator.push(carry as u64);
+
+        let mut remainder = 0u128;
+        for limb in numerator.iter_mut().rev() {
+            let value = (remainder << 64) | *limb as u128;
+            *limb = (value / alpha as u128) as u64;
+            remainder = value % alpha as u128;
+        }
+        numerator.truncate(order.len());
+        (remainder == 0).then_some(numerator)
+    })
+}
+
+/// Draws a Cauchy matrix `1 / (x_i + y_j)`, or `None` if a denominator is zero.
+fn cauchy_matrix<F: PrimeField>(grain: &mut Grain, width: usize) -> Option<Vec<Vec<F>>> {
+    let draws = loop {
+        let draws: Vec<F> = (0..2 * width)
+            .map(|_| grain.next_reduced_element())
+            .collect();
+        let distinct = draws
+            .iter()
+            .enumerate()
+            .all(|(i, draw)| !draws[..i].contains(draw));
+        if distinct {
+            break draws;
+        }
+    };
+    let (xs, ys) = draws.split_at(width);
+
+    xs.iter()
+        .map(|x| ys.iter().map(|y| (*x + y).inverse()).collect())
+        .collect()
+}
+
+/// Checks the MDS matrix against infinitely long subspace trails.
+///
+/// Mirrors the three algorithms of the reference script for one S-box per
+/// partial round: no power `M^i` with `i < t` is a scalar matrix or keeps a
+/// non-trivial subspace of `{v : v_0 = 0}` (where the S-box is inactive)
+/// invariant, and the orbit of `e_0` under `M^r` spans the whole space for
+/// every `r <= 4t`.
+fn is_secure_mds<F: PrimeField>(mds: &[Vec<F>]) -> bool {
+    let width = mds.len();
+    let mut power = mds.to_vec();
+    for i in 1..=4 * width {
+        if i < width {
+            let scalar = power[0][0];
+            let is_scalar = power.iter().enumerate().all(|(row, entries)| {
+                entries
+                    .iter()
+                    .enumerate()
+                    .all(|(col, entry)| *entry == if row == col { scalar } else { F::ZERO })
+            });
+            // Rows e_0^T·A^k; full rank means no invariant subspace with v_0 = 0.
+            let observability = krylov(&transpose(&power), width);
+            if is_scalar || rank(observability) < width {
+                return false;
+            }
+        }
+        if rank(krylov(&power, width)) < width {
+            return false;
+        }
+        power = mat_mul(&power, mds);
+    }
+    true
+}
+
+/// Returns `e_0, A·e_0, ..., A^{t-1}·e_0` as rows.
+fn krylov<F: PrimeField>(matrix: &[Vec<F>], width: usize) -> Vec<Vec<F>> {
+    let mut vector: Vec<F> = (0..width)
+        .map(|i| if i == 0 { F::ONE } else { F::ZERO })
+        .collect();
+    (0..width)
+        .map(|_| {
+            let current = vector.clone();
+            vector = matrix
+                .iter()
+                .map(|row| row.iter().zip(&current).map(|(a, b)| *a * b).sum())
+                .collect();
+            current
+        })
+        .collect()
+}
+
+fn transpose<F: PrimeField>(matrix: &[Vec<F>]) -> Vec<Vec<F>> {
+    (0..matrix.len())
+        .map(|col| matrix.iter().map(|row| row[col]).collect())
+        .collect()
+}
+
+fn mat_mul<F: PrimeField>(a: &[Vec<F>], b: &[Vec<F>]) -> Vec<Vec<F>> {
+    a.iter()
+        .map(|row| {
+            (0..b.len())
+                .map(|col| row.iter().zip(b).map(|(x, b_row)| *x * b_row[col]).sum())
+                .collect()
+        })
+        .collect()
+}
+
+/// Computes the rank of a matrix by Gaussian elimination.
+fn rank<F: PrimeField>(mut rows: Vec<Vec<F>>) -> usize {
+    let columns = rows.first().map_or(0, Vec::len);
+    let mut rank = 0;
+    for col in 0..columns {
+        let Some(pivot) = (rank..rows.len()).find(|row| !rows[*row][col].is_zero()) else {
+            continue;
+        };
+        rows.swap(rank, pivot);
+        let inverse = rows[rank][col].inverse().expect("pivot is non-zero");
+        let pivot_row: Vec<F> = rows[rank].iter().map(|entry| *entry * inverse).collect();
+        for row in rows.iter_mut().skip(rank + 1) {
+            let factor = row[col];
+            for (entry, pivot_entry) in row.iter_mut().zip(&pivot_row) {
+                *entry -= factor * pivot_entry;
+            }
+        }
+        rank += 1;
+    }
+    rank
+}
+
+/// Computes the cheapest secure `(full_rounds, partial_rounds)` for `x^alpha`
+/// over `F` at `security_level` bits, including the security margin.
+///
+/// Follows `calc_final_numbers_fixed` of the reference script, including its
+/// brute-force search order. Parameter sets such as circomlib's round the
+/// partial rounds up to a multiple of the width afterwards, which is how the
+/// shipped BN254 sets get 57 (t = 3) and 60 (t = 5) partial rounds.
+pub fn round_numbers<F: PrimeField>(
+    width: usize,
+    alpha: u64,
+    security_level: usize,
+) -> (usize, usize) {
+    let mut best = (0, 0);
+    let mut min_cost = usize::MAX;
+    let mut best_full_rounds = 0;
+    for partial_rounds in 1..500 {
+        // The reference applies the margin to the loop variable in place, so
+        // later full-round candidates are tested with the increased count.
+        let mut partial_candidate = partial_rounds;
+        for full_rounds in (4..100).step_by(2) {
+            if !is_secure_round_count::<F>(
+                width,
+                full_rounds,
+                partial_candidate,
+                alpha,
+                security_level,
+            ) {
+                continue;
+            }
+            let full_candidate = full_rounds + FULL_ROUNDS_MARGIN;
+            partial_candidate = (partial_candidate as f64 * PARTIAL_ROUNDS_MARGIN).ceil() as usize;
+            let cost = full_candidate * width + partial_candidate;
+            if cost < min_cost || (cost == min_cost && full_candidate < best_full_rounds) {
+                best = (full_candidate, partial_candidate);
+                min_cost = cost;
+                best_full_rounds = full_candidate;
+            }
+        }
+    }
+    best
+}
+
+/// Checks the statistical, interpolation and Gröbner-basis bounds for one round count.
+fn is_secure_round_count<F: PrimeField>(
+    width: usize,
+    full_rounds: usize,
+    partial_rounds: usize,
+    alpha: u64,
+    security_level: usize,
+) -> bool {
+    let t = width as f64;
+    let r_f = full_rounds as f64;
+    let r_p = partial_rounds as f64;
+    let m = security_level as f64;
+    let a = alpha as f64;
+    let n = F::MODULUS_BIT_SIZE as f64;
+    let log2_p = F::MODULUS
+        .as_ref()
+        .iter()
+        .rev()
+        .fold(0f64, |acc, limb| acc * 2f64.powi(64) + *limb as f64)
+        .log2();
+    let log_alpha_2 = 2f64.log(a);
+
+    let statistical: f64 = if m <= (log2_p - (a - 1.0) / 2.0).floor() * (t + 1.0) {
+        6.0
+    } else {
+        10.0
+    };
+    let interpolation = 1.0 + (log_alpha_2 * m.min(n)).ceil() + t.log(a).ceil() - r_p;
+    let groebner_1 = log_alpha_2 * m.min(log2_p) - r_p;
+    let groebner_2 = t - 1.0 + log_alpha_2 * (m / (t + 1.0)).min(log2_p / 2.0) - r_p;
+    let groebner_3 = (t - 2.0 + m / (2.0 * a.log2()) - r_p) / (t - 1.0);
+    let required = [
+        statistical,
+        interpolation,
+        groebner_1,
+        groebner_2,
+        groebner_3,
+    ]
+    .into_iter()
+    .map(f64::ceil)
+    .fold(f64::MIN, f64::max);
+    if r_f < required {
+        return false;
+    }
+
+    // Gröbner-basis bound from https://eprint.iacr.org/2023/537.pdf.
+    let r = (width / 3) as u64;
+    let over = (full_rounds as u64 - 1) * width as u64
+        + 2 * partial_rounds as u64
+        + r
+        + r * (full_rounds as u64 / 2)
+        + alpha;
+    let under = r * (full_rounds as u64 / 2) + partial_rounds as u64 + alpha;
+    let log2_binomial: f64 = (1..=under)
+        .map(|i| ((over - under + i) as f64 / i as f64).log2())
+        .sum();
+    (2.0 * log2_binomial).ceil() >= m
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::poseidon_hash::params::hasher::{
+        RoundParams, poseidon_bn254_3x3, poseidon_bn254_5x5,
+    };
+    use ark_bn254::Fr;
+
+    #[test]
+    fn test_generator_reproduces_bn254_5x5() {
+        let params = GeneratedParams::<Fr>::new(5, 5, 8, 60).unwrap();
+
+        // The checked-in file uses CRLF line endings.
+        assert_eq!(
+            params.to_rust_source(),
+            include_str!("hasher/poseidon_bn254_5x5.rs").replace("\r\n", "\n")
+        );
+        assert_eq!(
+            params.round_constants,
+            poseidon_bn254_5x5::Params::<Fr>::round_constants()
+        );
+    }
+
+    #[test]
+    fn test_generator_reproduces_bn254_3x3() {
+        let params = GeneratedParams::<Fr>::new(3, 5, 8, 57).unwrap();
+
+        assert_eq!(
+            params.to_rust_source(),
+            include_str!("hasher/poseidon_bn254_3x3.rs")
+        );
+        assert_eq!(
+            params.mds,
+            poseidon_bn254_3x3::Params::<Fr>::mds()
+                .map(Vec::from)
+                .to_vec()
+        );
+    }
+
+    #[test]
+    fn test_round_numbers() {
+        assert_eq!(round_numbers::<Fr>(3, 5, 128), (8, 56));
+        assert_eq!(round_numbers::<Fr>(5, 5, 128), (8, 56));
+
+        assert_eq!(
+            GeneratedParams::<Fr>::new(3, 2, 8, 57),
+            Err(SchnorrError::InvalidPoseidonParameters)
+        );
+        assert_eq!(
+            GeneratedParams::<Fr>::new(3, 5, 7, 57),
+            Err(SchnorrError::InvalidPoseidonParameters)
+        );
+    }
+}
@@ -1,10 +1,93 @@
-/// Poseidon Bn254 with 5 = 5 and EXPONENTIATION = 5
+/// Poseidon2 Bn254 with WIDTH = 3 and EXPONENTIATION = 5
+pub mod poseidon2_bn254_3x3;
+/// Poseidon Bn254 with WIDTH = 3 and EXPONENTIATION = 5
+pub mod poseidon_bn254_3x3;
+/// Poseidon Bn254 with WIDTH = 5 and EXPONENTIATION = 5
 pub mod poseidon_bn254_5x5;
+use crate::{
+    error::SchnorrError,
+    poseidon_hash::optimized::{OptimizedRounds, mat_vec},
+};
 use ark_ff::Field;
-use std::fmt::Debug;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt::Debug,
+    sync::{OnceLock, RwLock},
+};
 
-/// Trait definition of Round parameters of Poseidon
-pub trait RoundParams<F: Field>: Sbox<F> + Clone + Debug {
+/// Parsed configurations, keyed by the `TypeId`s of their parameter set and
+/// configuration type.
+type ConfigCache = RwLock<HashMap<(TypeId, TypeId), &'static (dyn Any + Send + Sync)>>;
+
+static CONFIGS: OnceLock<ConfigCache> = OnceLock::new();
+
+/// Returns the configuration of type `C` for the parameter set `P`, building
+/// it with `init` on first use and leaking it to share it afterwards.
+fn cached_config<P: 'static, C: Any + Send + Sync>(init: impl FnOnce() -> C) -> &'static C {
+    let key = (TypeId::of::<P>(), TypeId::of::<C>());
+    let configs = CONFIGS.get_or_init(Default::default);
+    let cached = configs
+        .read()
+        .expect("config cache is never poisoned")
+        .get(&key)
+        .copied();
+    let config = match cached {
+        Some(config) => config,
+        None => *configs
+            .write()
+            .expect("config cache is never poisoned")
+            .entry(key)
+            .or_insert_with(|| Box::leak(Box::new(init()))),
+    };
+
+    config
+        .downcast_ref()
+        .expect("configs are keyed by their type")
+}
+
+/// Round counts and constants of a parameter set as field elements.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoseidonConfig<F: Field, const WIDTH: usize> {
+    /// Number of full rounds.
+    pub full_rounds: usize,
+    /// Number of partial rounds.
+    pub partial_rounds: usize,
+    /// Round constants, `WIDTH` per round.
+    pub round_constants: Vec<F>,
+    /// MDS matrix with a size of WIDTH x WIDTH.
+    pub mds: [[F; WIDTH]; WIDTH],
+    /// Equivalent constants and sparse matrices for `Poseidon::permute_optimized`.
+    pub optimized: OptimizedRounds<F, WIDTH>,
+}
+
+impl<F: Field, const WIDTH: usize> PoseidonConfig<F, WIDTH> {
+    /// Parses the hex constants of the parameter set `P`.
+    pub fn new<P: RoundParams<F, WIDTH>>() -> Self {
+        let full_rounds = P::full_rounds();
+        let partial_rounds = P::partial_rounds();
+        let round_constants = P::round_constants();
+        let mds = P::mds();
+        let optimized = OptimizedRounds::new(full_rounds, partial_rounds, &round_constants, &mds);
+
+        PoseidonConfig {
+            full_rounds,
+            partial_rounds,
+            round_constants,
+            mds,
+            optimized,
+        }
+    }
+
+    /// Multiplies the state by the MDS matrix for the MixLayer operation.
+    pub fn apply_mds(&self, state: &[F; WIDTH]) -> [F; WIDTH] {
+        mat_vec(&self.mds, state)
+    }
+}
+
+/// Trait definition of Round parameters of Poseidon over a state of `WIDTH` elements
+pub trait RoundParams<F: Field, const WIDTH: usize>: Sbox<F> + Clone + Debug + 'static {
     /// Returns a number of full rounds.
     fn full_rounds() -> usize;
     /// Returns a number of partial rounds.
@@ -14,7 +97,7 @@ pub trait RoundParams<F: Field>: Sbox<F> + Clone + Debug {
     fn round_constants_count() -> usize {
         let partial_rounds = Self::partial_rounds();
         let full_rounds = Self::full_rounds();
-        (partial_rounds + full_rounds) * 5
+        (partial_rounds + full_rounds) * WIDTH
     }
 
     /// Returns round constants array to be used in permutation.
@@ -29,16 +112,17 @@ pub trait RoundParams<F: Field>: Sbox<F> + Clone + Debug {
     }
 
     /// Returns relevant constants for the given round.
-    fn load_round_constants(round: usize, round_consts: &[F]) -> [F; 5] {
-        let mut result = [F::ZERO; 5];
-        for i in 0..5 {
-            result[i] = round_consts[round * 5 + i];
-        }
-        result
+    fn load_round_constants(round: usize, round_consts: &[F]) -> [F; WIDTH] {
+        std::array::from_fn(|i| round_consts[round * WIDTH + i])
     }
 
-    /// Returns MDS matrix with a size of 5 x 5.
-    fn mds() -> [[F; 5]; 5] {
+    /// Returns the parsed configuration, built on first use and shared afterwards.
+    fn config() -> &'static PoseidonConfig<F, WIDTH> {
+        cached_config::<Self, _>(PoseidonConfig::new::<Self>)
+    }
+
+    /// Returns MDS matrix with a size of WIDTH x WIDTH.
+    fn mds() -> [[F; WIDTH]; WIDTH] {
         let mds_raw = Self::mds_raw();
         mds_raw.map(|row| row.map(|item| hex_to_field(item)))
     }
@@ -46,32 +130,92 @@ pub trait RoundParams<F: Field>: Sbox<F> + Clone + Debug {
     /// Returns round constants in its hex string form.
     fn round_constants_raw() -> Vec<&'static str>;
     /// Returns MDS martrix in its hex string form.
-    fn mds_raw() -> [[&'static str; 5]; 5];
+    fn mds_raw() -> [[&'static str; WIDTH]; WIDTH];
     /// Add round constants to the state values
     /// for the AddRoundConstants operation.
-    fn apply_round_constants(state: &[F; 5], round_consts: &[F; 5]) -> [F; 5] {
-        let mut next_state = [F::ZERO; 5];
-        for i in 0..5 {
-            let state = state[i];
-            let round_const = round_consts[i];
-            let sum = state + round_const;
-            next_state[i] = sum;
-        }
-        next_state
+    fn apply_round_constants(state: &[F; WIDTH], round_consts: &[F; WIDTH]) -> [F; WIDTH] {
+        std::array::from_fn(|i| state[i] + round_consts[i])
     }
     /// Compute MDS matrix for MixLayer operation.
-    fn apply_mds(state: &[F; 5]) -> [F; 5] {
-        let mut new_state = [F::ZERO; 5];
-        let mds = Self::mds();
-        for i in 0..5 {
-            for j in 0..5 {
-                let mds_ij = &mds[i][j];
-                let m_product = state[j] * mds_ij;
-                new_state[i] += m_product;
-            }
+    fn apply_mds(state: &[F; WIDTH]) -> [F; WIDTH] {
+        Self::config().apply_mds(state)
+    }
+}
+
+/// Round counts and constants of a Poseidon2 parameter set as field elements.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Poseidon2Config<F: Field, const WIDTH: usize> {
+    /// Number of external (full) rounds.
+    pub full_rounds: usize,
+    /// Number of internal (partial) rounds.
+    pub partial_rounds: usize,
+    /// Constants of the external rounds, both halves in order.
+    pub external_round_constants: Vec<[F; WIDTH]>,
+    /// The constant added to the first element in each internal round.
+    pub internal_round_constants: Vec<F>,
+    /// The internal matrix is the all-ones matrix plus `diag(internal_diagonal)`.
+    pub internal_diagonal: [F; WIDTH],
+}
+
+impl<F: Field, const WIDTH: usize> Poseidon2Config<F, WIDTH> {
+    /// Parses the hex constants of the parameter set `P`.
+    pub fn new<P: Poseidon2Params<F, WIDTH>>() -> Self {
+        let full_rounds = P::full_rounds();
+        let partial_rounds = P::partial_rounds();
+        let round_constants: Vec<F> = P::round_constants_raw()
+            .iter()
+            .map(|x| hex_to_field(x))
+            .collect();
+        assert_eq!(round_constants.len(), full_rounds * WIDTH + partial_rounds);
+
+        let first_round_end = full_rounds / 2 * WIDTH;
+        let second_round_end = first_round_end + partial_rounds;
+        let external_round_constants = round_constants[..first_round_end]
+            .iter()
+            .chain(&round_constants[second_round_end..])
+            .copied()
+            .collect::<Vec<F>>()
+            .chunks(WIDTH)
+            .map(|chunk| std::array::from_fn(|i| chunk[i]))
+            .collect();
+
+        Poseidon2Config {
+            full_rounds,
+            partial_rounds,
+            external_round_constants,
+            internal_round_constants: round_constants[first_round_end..second_round_end].to_vec(),
+            internal_diagonal: P::internal_diagonal_raw().map(|item| hex_to_field(item)),
         }
-        new_state
     }
+
+    /// Multiplies the state by the internal matrix in `O(WIDTH)`.
+    pub fn apply_internal_matrix(&self, state: &[F; WIDTH]) -> [F; WIDTH] {
+        let sum: F = state.iter().sum();
+        std::array::from_fn(|i| sum + self.internal_diagonal[i] * state[i])
+    }
+}
+
+/// Trait definition of Poseidon2 parameters over a state of `WIDTH` elements.
+///
+/// The external matrix is fixed by the width, so a parameter set only
+/// provides round counts, round constants and the internal diagonal.
+pub trait Poseidon2Params<F: Field, const WIDTH: usize>: Sbox<F> + Clone + Debug + 'static {
+    /// Returns a number of external (full) rounds.
+    fn full_rounds() -> usize;
+    /// Returns a number of internal (partial) rounds.
+    fn partial_rounds() -> usize;
+
+    /// Returns the parsed configuration, built on first use and shared afterwards.
+    fn config() -> &'static Poseidon2Config<F, WIDTH> {
+        cached_config::<Self, _>(Poseidon2Config::new::<Self>)
+    }
+
+    /// Returns round constants in its hex string form: `WIDTH` per external
+    /// round and one per internal round, in round order, as drawn from the
+    /// Grain LFSR of the reference implementation.
+    fn round_constants_raw() -> Vec<&'static str>;
+    /// Returns the internal diagonal in its hex string form.
+    fn internal_diagonal_raw() -> [&'static str; WIDTH];
 }
 
 /// Trait definition for Sbox operation of Poseidon
@@ -83,11 +227,29 @@ pub trait Sbox<F: Field> {
 }
 
 /// Returns congruent field element for the given hex string.
+///
+/// Intended for the built-in parameter tables; panics if `s` is malformed.
+/// Use `try_hex_to_field` for untrusted input.
 pub fn hex_to_field<F: Field>(s: &str) -> F {
-    let s = &s[2..];
-    let mut bytes = hex::decode(s).expect("Invalid params");
+    try_hex_to_field(s).expect("Invalid params")
+}
+
+/// Parses a `0x`-prefixed big-endian hex string into a field element.
+///
+/// Fails if the prefix is missing, the digits are not valid hex, or the value
+/// is not below the field modulus.
+pub fn try_hex_to_field<F: Field>(s: &str) -> Result<F, SchnorrError> {
+    let digits = s.strip_prefix("0x").ok_or(SchnorrError::InvalidHex)?;
+    let mut bytes = hex::decode(digits).map_err(|_| SchnorrError::InvalidHex)?;
     bytes.reverse();
-    let mut bytes_wide: [u8; 64] = [0; 64];
-    bytes_wide[..bytes.len()].copy_from_slice(&bytes[..]);
-    F::from_random_bytes(&bytes_wide).unwrap()
+
+    let size = F::BasePrimeField::default().compressed_size();
+    if bytes.len() > size {
+        return Err(SchnorrError::InvalidHex);
+    }
+    bytes.resize(size, 0);
+    let element = F::BasePrimeField::deserialize_compressed(bytes.as_slice())
+        .map_err(|_| SchnorrError::InvalidHex)?;
+
+    Ok(F::from_base_prime_field(element))
 }
@@ -26,7 +26,7 @@ impl<F: Field> Sbox<F> for Params<F> {
     }
 }
 
-impl<F: Field> RoundParams<F> for Params<F> {
+impl<F: Field> RoundParams<F, 5> for Params<F> {
     fn partial_rounds() -> usize {
         60
     }
@@ -1,2 +1,4 @@
+/// Grain LFSR parameter generator
+pub mod generator;
 /// Params and constants for hashing functions
 pub mod hasher;
@@ -0,0 +1,152 @@
+//! Native Poseidon2 permutation.
+//!
+//! Inspired by:
+//! - Grassi, Khovratovich, Schofnegger, "Poseidon2: A Faster Version of the
+//!   Poseidon Hash Function": https://eprint.iacr.org/2023/323.pdf
+//! - The HorizenLabs reference implementation and its BN254 test vectors
+//!
+//! Poseidon2 keeps the Hades round structure but replaces the dense MDS
+//! matrix with two cheap linear layers. External (full) rounds multiply by a
+//! fixed matrix built from small integers, internal (partial) rounds by the
+//! all-ones matrix plus a diagonal, both in `O(WIDTH)`. Internal rounds add
+//! a single constant to the first element, and the external matrix is also
+//! applied once before the first round.
+
+use super::params::hasher::{Poseidon2Params, poseidon2_bn254_3x3::Params};
+use ark_ff::Field;
+use std::marker::PhantomData;
+
+/// Poseidon2 over a state of `WIDTH` elements with the parameter set `P`.
+///
+/// The defaults are the BN254 width-3 parameters of the reference
+/// implementation. Supported widths are 2, 3 and multiples of 4.
+#[derive(Debug, Clone)]
+pub struct Poseidon2<F: Field, const WIDTH: usize = 3, P: Poseidon2Params<F, WIDTH> = Params<F>> {
+    /// Constructs an array for the inputs.
+    inputs: [F; WIDTH],
+    _params: PhantomData<P>,
+}
+
+impl<F: Field, const WIDTH: usize, P: Poseidon2Params<F, WIDTH>> Poseidon2<F, WIDTH, P> {
+    /// Create the objects.
+    pub fn new(inputs: [F; WIDTH]) -> Self {
+        Poseidon2 {
+            inputs,
+            _params: PhantomData,
+        }
+    }
+
+    /// Applies the initial linear layer, the first half of the external
+    /// rounds, the internal rounds and the second half of the external rounds.
+    pub fn permute(&self) -> [F; WIDTH] {
+        let config = P::config();
+        let (first_round_constants, third_round_constants) = config
+            .external_round_constants
+            .split_at(config.full_rounds / 2);
+
+        let mut state = external_matrix(&self.inputs);
+        for round_consts in first_round_constants {
+            state = external_matrix(&external_sbox::<F, WIDTH, P>(&state, round_consts));
+        }
+
+        for round_const in &config.internal_round_constants {
+            state[0] = P::sbox_f(state[0] + round_const);
+            state = config.apply_internal_matrix(&state);
+        }
+
+        for round_consts in third_round_constants {
+            state = external_matrix(&external_sbox::<F, WIDTH, P>(&state, round_consts));
+        }
+
+        state
+    }
+}
+
+/// Adds the round constants and applies the S-box to every element.
+fn external_sbox<F: Field, const WIDTH: usize, P: Poseidon2Params<F, WIDTH>>(
+    state: &[F; WIDTH],
+    round_consts: &[F; WIDTH],
+) -> [F; WIDTH] {
+    std::array::from_fn(|i| P::sbox_f(state[i] + round_consts[i]))
+}
+
+/// Multiplies the state by the external matrix.
+///
+/// Widths 2 and 3 use `circ(2, 1)` and `circ(2, 1, 1)`. Multiples of 4 apply
+/// `M4` to each chunk of four and then `circ(2, 1, ..., 1)` across chunks,
+/// which together give `circ(2·M4, M4, ..., M4)`.
+fn external_matrix<F: Field, const WIDTH: usize>(state: &[F; WIDTH]) -> [F; WIDTH] {
+    match WIDTH {
+        2 | 3 => {
+            let sum: F = state.iter().sum();
+            state.map(|x| x + sum)
+        }
+        _ => {
+            assert!(
+                WIDTH.is_multiple_of(4),
+                "Poseidon2 supports widths 2, 3 and multiples of 4"
+            );
+            let mut mixed = *state;
+            for chunk in mixed.chunks_exact_mut(4) {
+                chunk.copy_from_slice(&m4([chunk[0], chunk[1], chunk[2], chunk[3]]));
+            }
+            let sums: [F; 4] =
+                std::array::from_fn(|i| mixed.iter().skip(i).step_by(4).copied().sum());
+            std::array::from_fn(|i| mixed[i] + sums[i % 4])
+        }
+    }
+}
+
+/// Multiplies four elements by `M4 = [[5, 7, 1, 3], [4, 6, 1, 1], [1, 3, 5, 7], [1, 1, 4, 6]]`.
+fn m4<F: Field>([x0, x1, x2, x3]: [F; 4]) -> [F; 4] {
+    let t0 = x0 + x1;
+    let t1 = x2 + x3;
+    let t2 = x1.double() + t1;
+    let t3 = x3.double() + t0;
+    let t4 = t1.double().double() + t3;
+    let t5 = t0.double().double() + t2;
+    let t6 = t3 + t5;
+    let t7 = t2 + t4;
+    [t6, t5, t7, t4]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::poseidon_hash::params::hasher::hex_to_field;
+    use ark_bn254::Fr;
+
+    #[test]
+    fn test_native_poseidon2_3x3() {
+        // Test vector of the reference implementation for BN254, t = 3.
+        let inputs: [Fr; 3] = [
+            "0x0000000000000000000000000000000000000000000000000000000000000000",
+            "0x0000000000000000000000000000000000000000000000000000000000000001",
+            "0x0000000000000000000000000000000000000000000000000000000000000002",
+        ]
+        .map(hex_to_field);
+
+        let outputs: [Fr; 3] = [
+            "0x0bb61d24daca55eebcb1929a82650f328134334da98ea4f847f760054f4a3033",
+            "0x303b6f7c86d043bfcbcc80214f26a30277a15d3f74ca654992defe7ff8d03570",
+            "0x1ed25194542b12eef8617361c3ba7c52e660b145994427cc86296242cf766ec8",
+        ]
+        .map(hex_to_field);
+
+        assert_eq!(Poseidon2::<Fr>::new(inputs).permute(), outputs);
+    }
+
+    #[test]
+    fn test_external_matrix_m4() {
+        let unit = |i: usize| std::array::from_fn(|j| Fr::from((i == j) as u64));
+        let columns = [[5, 4, 1, 1], [7, 6, 3, 1], [1, 1, 5, 4], [3, 1, 7, 6]];
+        for (i, column) in columns.iter().enumerate() {
+            assert_eq!(m4(unit(i)), column.map(Fr::from));
+        }
+
+        // circ(2·M4, M4) on the first unit vector of width 8.
+        let state: [Fr; 8] = std::array::from_fn(|j| Fr::from((j == 0) as u64));
+        let expected = [10, 8, 2, 2, 5, 4, 1, 1].map(Fr::from);
+        assert_eq!(external_matrix(&state), expected);
+    }
+}
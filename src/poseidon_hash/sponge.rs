@@ -1,21 +1,28 @@
-use super::Poseidon;
+use super::{Permutation, Poseidon};
 use ark_ff::Field;
+use std::marker::PhantomData;
 
 /// Constructs objects.
+///
+/// `P` is the permutation, by default Poseidon with the BN254 width-5
+/// parameters; `Poseidon2` works as well.
 #[derive(Clone, Debug)]
-pub struct PoseidonSponge<F: Field> {
+pub struct PoseidonSponge<F: Field, const WIDTH: usize = 5, P: Permutation<F, WIDTH> = Poseidon<F>>
+{
     /// Constructs a vector for the inputs.
     inputs: Vec<F>,
     /// Internal state
-    state: [F; 5],
+    state: [F; WIDTH],
+    _params: PhantomData<P>,
 }
 
-impl<F: Field> PoseidonSponge<F> {
+impl<F: Field, const WIDTH: usize, P: Permutation<F, WIDTH>> PoseidonSponge<F, WIDTH, P> {
     /// Create objects.
     pub fn new() -> Self {
         Self {
             inputs: Vec::new(),
-            state: [F::ZERO; 5],
+            state: [F::ZERO; WIDTH],
+            _params: PhantomData,
         }
     }
 
@@ -25,10 +32,10 @@ impl<F: Field> PoseidonSponge<F> {
     }
 
     /// Absorb the data in and split it into
-    /// chunks of size 5.
-    fn load_state(chunk: &[F]) -> [F; 5] {
-        assert!(chunk.len() <= 5);
-        let mut fixed_chunk = [F::ZERO; 5];
+    /// chunks of size WIDTH.
+    fn load_state(chunk: &[F]) -> [F; WIDTH] {
+        assert!(chunk.len() <= WIDTH);
+        let mut fixed_chunk = [F::ZERO; WIDTH];
         fixed_chunk[..chunk.len()].copy_from_slice(chunk);
         fixed_chunk
     }
@@ -40,18 +47,17 @@ impl<F: Field> PoseidonSponge<F> {
             self.inputs.push(F::ZERO);
         }
 
-        for chunk in self.inputs.chunks(5) {
-            let mut input = [F::ZERO; 5];
+        for chunk in self.inputs.chunks(WIDTH) {
+            let mut input = [F::ZERO; WIDTH];
 
             // Absorb
             let loaded_state = Self::load_state(chunk);
-            for i in 0..5 {
+            for i in 0..WIDTH {
                 input[i] = loaded_state[i] + self.state[i];
             }
 
             // Permute
-            let pos = Poseidon::new(input);
-            self.state = pos.permute();
+            self.state = P::apply(input);
         }
 
         // Clear the inputs, and return the result
@@ -60,7 +66,9 @@ impl<F: Field> PoseidonSponge<F> {
     }
 }
 
-impl<F: Field> Default for PoseidonSponge<F> {
+impl<F: Field, const WIDTH: usize, P: Permutation<F, WIDTH>> Default
+    for PoseidonSponge<F, WIDTH, P>
+{
     fn default() -> Self {
         Self::new()
     }
@@ -0,0 +1,644 @@
+//! Proactive share refresh and signer-set resharing for a Shamir-shared group key.
+//!
+//! Inspired by:
+//! - Herzberg et al., "Proactive Secret Sharing" (Crypto '95)
+//! - Desmedt, Jajodia, "Redistributing Secret Shares to New Access Structures" (1997)
+//!
+//! Both protocols keep the group public key and therefore every existing
+//! signature valid; only the shares change.
+//!
+//! Refresh: every participant deals a random polynomial `δ_i` with
+//! `δ_i(0) = 0`, committed with Feldman commitments whose constant term must
+//! be the identity. Participant `j` adds `Σ δ_i(j)` to its share, so the
+//! shared secret is unchanged while old shares become useless against the
+//! new ones. `refresh_verification_share` updates the public verification
+//! shares `Y_j` the same way from the commitments.
+//!
+//! Resharing: a set of at least `threshold` old signers each deals its
+//! Lagrange-weighted share `λ_i·s_i` to a new set of participants with a new
+//! threshold. The constant-term commitment must equal `λ_i·Y_i` for the
+//! dealer's public verification share `Y_i`, and the constant terms must add
+//! up to the group public key. `reshare_verification_share` computes the new
+//! participants' verification shares.
+
+use ark_ec::CurveGroup;
+use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use std::fmt;
+
+use crate::{
+    dkg::DealtShare,
+    error::SchnorrError,
+    frost::KeyShare,
+    keypair::Keypair,
+    shamir::{self, Polynomial, SecretShare, lagrange_coefficient},
+};
+
+/// A dealer's broadcast Feldman commitments for a refresh or resharing round.
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct CommitmentPackage<G: CurveGroup> {
+    pub index: u32,
+    pub commitments: Vec<G>,
+}
+
+/// One dealer's polynomial for a refresh or resharing round.
+///
+/// Its `Debug` output omits the polynomial.
+pub struct Dealer<F: Field, G: CurveGroup> {
+    index: u32,
+    participants: u32,
+    polynomial: Polynomial<F>,
+    package: CommitmentPackage<G>,
+}
+
+impl<F: Field, G: CurveGroup> fmt::Debug for Dealer<F, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Dealer")
+            .field("index", &self.index)
+            .field("participants", &self.participants)
+            .field("package", &self.package)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F: Field, G: CurveGroup<ScalarField = F>> Dealer<F, G> {
+    /// Starts a refresh round for participant `index` with a zero-constant-term polynomial.
+    pub fn refresh(index: u32, threshold: u32, participants: u32) -> Result<Self, SchnorrError> {
+        shamir::check_threshold(threshold, participants)?;
+        if index == 0 || index > participants {
+            return Err(SchnorrError::InvalidParticipantIndex(index));
+        }
+
+        Ok(Self::with_polynomial(
+            index,
+            participants,
+            Polynomial::random(F::ZERO, threshold as usize - 1),
+        ))
+    }
+
+    /// Starts a resharing round in which `share` is dealt to a new participant set.
+    ///
+    /// `old_signers` lists the indices of all old participants taking part and
+    /// must contain `share.index`; at least the old threshold of them is needed.
+    pub fn reshare(
+        share: &KeyShare<F, G>,
+        old_signers: &[u32],
+        new_threshold: u32,
+        new_participants: u32,
+    ) -> Result<Self, SchnorrError> {
+        shamir::check_threshold(new_threshold, new_participants)?;
+        let lambda: F = lagrange_coefficient(share.index, old_signers)?;
+
+        Ok(Self::with_polynomial(
+            share.index,
+            new_participants,
+            Polynomial::random(
+                lambda * share.keypair.private_key,
+                new_threshold as usize - 1,
+            ),
+        ))
+    }
+
+    fn with_polynomial(index: u32, participants: u32, polynomial: Polynomial<F>) -> Self {
+        let package = CommitmentPackage {
+            index,
+            commitments: polynomial.commitments(),
+        };
+
+        Dealer {
+            index,
+            participants,
+            polynomial,
+            package,
+        }
+    }
+
+    /// Returns the commitments to broadcast.
+    pub fn package(&self) -> &CommitmentPackage<G> {
+        &self.package
+    }
+
+    /// Returns the share to send privately to participant `receiver`.
+    pub fn share_for(&self, receiver: u32) -> Result<DealtShare<F>, SchnorrError> {
+        if receiver == 0 || receiver > self.participants {
+            return Err(SchnorrError::InvalidParticipantIndex(receiver));
+        }
+
+        Ok(DealtShare {
+            sender: self.index,
+            receiver,
+            value: self.polynomial.evaluate(F::from(receiver)),
+        })
+    }
+}
+
+impl<G: CurveGroup> CommitmentPackage<G> {
+    /// Checks the commitment count and that the committed constant term is `constant`.
+    ///
+    /// Fails with `InvalidThreshold` for a zero threshold, with `LengthMismatch`
+    /// for the wrong number of commitments and with `InvalidShare` naming the
+    /// dealer for the wrong constant term.
+    fn verify_constant(&self, threshold: u32, constant: G) -> Result<(), SchnorrError> {
+        if threshold == 0 {
+            return Err(SchnorrError::InvalidThreshold {
+                threshold,
+                participants: 0,
+            });
+        }
+        if self.commitments.len() != threshold as usize {
+            return Err(SchnorrError::LengthMismatch {
+                expected: threshold as usize,
+                found: self.commitments.len(),
+            });
+        }
+        if self.commitments[0] != constant {
+            return Err(SchnorrError::InvalidShare(self.index));
+        }
+        Ok(())
+    }
+
+    /// Checks a refresh package: `threshold` commitments and a zero constant term.
+    pub fn verify_refresh(&self, threshold: u32) -> Result<(), SchnorrError> {
+        self.verify_constant(threshold, G::zero())
+    }
+
+    /// Checks a resharing package against the dealer's old verification share `Y_i`.
+    pub fn verify_reshare(
+        &self,
+        verification_share: G,
+        old_signers: &[u32],
+        new_threshold: u32,
+    ) -> Result<(), SchnorrError> {
+        let lambda: G::ScalarField = lagrange_coefficient(self.index, old_signers)?;
+
+        self.verify_constant(new_threshold, verification_share * lambda)
+    }
+}
+
+/// Checks a received share against the sender's commitments and returns its value.
+fn checked_share<F: Field, G: CurveGroup<ScalarField = F>>(
+    index: u32,
+    package: &CommitmentPackage<G>,
+    shares: &[DealtShare<F>],
+) -> Result<F, SchnorrError> {
+    let share = shares
+        .iter()
+        .find(|share| share.sender == package.index && share.receiver == index)
+        .ok_or(SchnorrError::InvalidShare(package.index))?;
+    let secret_share = SecretShare {
+        index,
+        value: share.value,
+    };
+    if !shamir::verify_share(&secret_share, &package.commitments) {
+        return Err(SchnorrError::InvalidShare(package.index));
+    }
+
+    Ok(share.value)
+}
+
+/// Fails with `InvalidParticipantIndex` if two packages come from the same dealer.
+fn check_distinct_dealers<G: CurveGroup>(
+    packages: &[CommitmentPackage<G>],
+) -> Result<(), SchnorrError> {
+    for (position, package) in packages.iter().enumerate() {
+        if packages[..position]
+            .iter()
+            .any(|other| other.index == package.index)
+        {
+            return Err(SchnorrError::InvalidParticipantIndex(package.index));
+        }
+    }
+    Ok(())
+}
+
+/// Adds the refresh shares dealt to `key_share` and returns the refreshed share.
+///
+/// Fails with `InvalidThreshold` if `threshold` is zero or exceeds the number
+/// of dealers, with `InvalidParticipantIndex` for a repeated dealer, and with
+/// the error of `verify_refresh` for a malformed package. Every share is
+/// checked against the packages, and a missing or invalid one fails with
+/// `InvalidShare`.
+pub fn refresh_share<F: Field, G: CurveGroup<ScalarField = F>>(
+    key_share: &KeyShare<F, G>,
+    threshold: u32,
+    packages: &[CommitmentPackage<G>],
+    shares: &[DealtShare<F>],
+) -> Result<KeyShare<F, G>, SchnorrError> {
+    shamir::check_threshold(threshold, packages.len() as u32)?;
+    check_distinct_dealers(packages)?;
+    for package in packages {
+        package.verify_refresh(threshold)?;
+    }
+
+    let mut secret_share = key_share.keypair.private_key;
+    for package in packages {
+        secret_share += checked_share(key_share.index, package, shares)?;
+    }
+
+    Ok(KeyShare {
+        index: key_share.index,
+        keypair: Keypair::from_private_key(secret_share),
+        group_public_key: key_share.group_public_key,
+    })
+}
+
+/// Computes participant `index`'s verification share after a refresh from its
+/// old one and the broadcasts alone: `Y_j + Σ δ_i(j)·G`.
+///
+/// `packages` must have passed `verify_refresh`.
+pub fn refresh_verification_share<F: Field, G: CurveGroup<ScalarField = F>>(
+    index: u32,
+    verification_share: G,
+    packages: &[CommitmentPackage<G>],
+) -> G {
+    verification_share + reshare_verification_share::<F, G>(index, packages)
+}
+
+/// Computes new participant `index`'s verification share after a resharing
+/// from the broadcasts alone.
+///
+/// `packages` must have passed `verify_reshare`.
+pub fn reshare_verification_share<F: Field, G: CurveGroup<ScalarField = F>>(
+    index: u32,
+    packages: &[CommitmentPackage<G>],
+) -> G {
+    packages
+        .iter()
+        .map(|package| shamir::evaluate_commitments(&package.commitments, index))
+        .sum()
+}
+
+/// Combines the resharing shares dealt to new participant `index`.
+///
+/// The dealers of `packages` are the old signers, and `verification_shares`
+/// holds their old verification shares `Y_i` in the same order. Fails with
+/// `LengthMismatch` if the two lengths differ, with
+/// `InvalidParticipantIndex` for a repeated dealer, with the error of
+/// `verify_reshare` for a malformed package, with `GroupKeyMismatch` if the
+/// constant terms do not add up to `group_public_key`, and with
+/// `InvalidShare` for a missing or invalid share.
+pub fn reshare_finalize<F: Field, G: CurveGroup<ScalarField = F>>(
+    index: u32,
+    new_threshold: u32,
+    group_public_key: G,
+    packages: &[CommitmentPackage<G>],
+    verification_shares: &[G],
+    shares: &[DealtShare<F>],
+) -> Result<KeyShare<F, G>, SchnorrError> {
+    if verification_shares.len() != packages.len() {
+        return Err(SchnorrError::LengthMismatch {
+            expected: packages.len(),
+            found: verification_shares.len(),
+        });
+    }
+    check_distinct_dealers(packages)?;
+    let old_signers: Vec<u32> = packages.iter().map(|package| package.index).collect();
+    for (package, verification_share) in packages.iter().zip(verification_shares) {
+        package.verify_reshare(*verification_share, &old_signers, new_threshold)?;
+    }
+
+    let committed_key: G = packages.iter().map(|package| package.commitments[0]).sum();
+    if committed_key != group_public_key {
+        return Err(SchnorrError::GroupKeyMismatch);
+    }
+
+    let mut secret_share = F::ZERO;
+    for package in packages {
+        secret_share += checked_share(index, package, shares)?;
+    }
+
+    Ok(KeyShare {
+        index,
+        keypair: Keypair::from_private_key(secret_share),
+        group_public_key,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        frost::{Session, commit, split_keypair},
+        transcript::{Message, PoseidonTranscript},
+    };
+
+    type F = ark_bn254::Fr;
+    type G = ark_bn254::G1Projective;
+
+    fn secret_of(shares: &[KeyShare<F, G>]) -> F {
+        let shares: Vec<_> = shares
+            .iter()
+            .map(|share| SecretShare {
+                index: share.index,
+                value: share.keypair.private_key,
+            })
+            .collect();
+        shamir::interpolate(&shares).unwrap()
+    }
+
+    #[test]
+    fn test_refresh_keeps_secret() {
+        let keypair = Keypair::<F, G>::generate();
+        let old_shares = split_keypair(&keypair, 3, 5).unwrap();
+
+        let dealers: Vec<_> = (1..=5)
+            .map(|index| Dealer::<F, G>::refresh(index, 3, 5).unwrap())
+            .collect();
+        let packages: Vec<_> = dealers.iter().map(|d| d.package().clone()).collect();
+        for package in &packages {
+            package.verify_refresh(3).unwrap();
+        }
+        let new_shares: Vec<_> = old_shares
+            .iter()
+            .map(|share| {
+                let dealt: Vec<_> = dealers
+                    .iter()
+                    .map(|dealer| dealer.share_for(share.index).unwrap())
+                    .collect();
+                refresh_share(share, 3, &packages, &dealt).unwrap()
+            })
+            .collect();
+
+        assert_eq!(secret_of(&old_shares[..3]), keypair.private_key);
+        assert_eq!(secret_of(&new_shares[2..]), keypair.private_key);
+        assert_eq!(secret_of(&new_shares[..3]), secret_of(&old_shares[1..4]));
+        for (old, new) in old_shares.iter().zip(&new_shares) {
+            assert_ne!(old.keypair.private_key, new.keypair.private_key);
+        }
+
+        // Old and new shares no longer combine.
+        let mixed = [
+            old_shares[0].clone(),
+            new_shares[1].clone(),
+            new_shares[2].clone(),
+        ];
+        assert_ne!(secret_of(&mixed), keypair.private_key);
+    }
+
+    #[test]
+    fn test_refreshed_shares_sign_with_frost() {
+        let keypair = Keypair::<F, G>::generate();
+        let old_shares = split_keypair(&keypair, 2, 3).unwrap();
+        let dealers: Vec<_> = (1..=3)
+            .map(|index| Dealer::<F, G>::refresh(index, 2, 3).unwrap())
+            .collect();
+        let packages: Vec<_> = dealers.iter().map(|d| d.package().clone()).collect();
+        let new_shares: Vec<_> = old_shares
+            .iter()
+            .map(|share| {
+                let dealt: Vec<_> = dealers
+                    .iter()
+                    .map(|dealer| dealer.share_for(share.index).unwrap())
+                    .collect();
+                refresh_share(share, 2, &packages, &dealt).unwrap()
+            })
+            .collect();
+        for (old, new) in old_shares.iter().zip(&new_shares) {
+            assert_eq!(
+                refresh_verification_share(new.index, old.keypair.public_key, &packages),
+                new.keypair.public_key
+            );
+        }
+
+        let signers = [&new_shares[0], &new_shares[2]];
+        let (nonces, commitments): (Vec<_>, Vec<_>) =
+            signers.iter().map(|s| commit::<F, G>(s.index)).unzip();
+        let message = F::from(16);
+        let session =
+            Session::new(keypair.public_key, &commitments, Message::Scalar(message)).unwrap();
+        let signature_shares: Vec<_> = signers
+            .iter()
+            .zip(nonces)
+            .map(|(share, nonces)| session.sign(share, nonces).unwrap())
+            .collect();
+
+        // The old verification shares no longer match the refreshed signature shares.
+        let stale = [
+            old_shares[0].keypair.public_key,
+            old_shares[2].keypair.public_key,
+        ];
+        assert_eq!(
+            session.aggregate(&signature_shares, &stale).err(),
+            Some(SchnorrError::InvalidPartialSignatures(vec![0, 1]))
+        );
+
+        let verification_shares: Vec<_> = [&old_shares[0], &old_shares[2]]
+            .iter()
+            .map(|old| refresh_verification_share(old.index, old.keypair.public_key, &packages))
+            .collect();
+        let signature = session
+            .aggregate(&signature_shares, &verification_shares)
+            .unwrap();
+        let mut transcript = PoseidonTranscript::new();
+        assert!(
+            signature
+                .verify(keypair.public_key, &mut transcript, message)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_refresh_rejects_malformed_package() {
+        let dealer = Dealer::<F, G>::refresh(1, 2, 3).unwrap();
+        let mut package = dealer.package().clone();
+        assert_eq!(
+            package.verify_refresh(0),
+            Err(SchnorrError::InvalidThreshold {
+                threshold: 0,
+                participants: 0
+            })
+        );
+
+        package.commitments.clear();
+        assert_eq!(
+            package.verify_refresh(2),
+            Err(SchnorrError::LengthMismatch {
+                expected: 2,
+                found: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_refresh_rejects_nonzero_constant() {
+        let dealer = Dealer::<F, G>::refresh(1, 2, 3).unwrap();
+        let mut package = dealer.package().clone();
+        package.commitments[0] = Keypair::<F, G>::generate().public_key;
+
+        assert_eq!(
+            package.verify_refresh(2),
+            Err(SchnorrError::InvalidShare(1))
+        );
+    }
+
+    #[test]
+    fn test_reshare_to_new_signer_set() {
+        let keypair = Keypair::<F, G>::generate();
+        let old_shares = split_keypair(&keypair, 2, 3).unwrap();
+        let old_signers = [1, 3];
+
+        let dealers: Vec<_> = [&old_shares[0], &old_shares[2]]
+            .iter()
+            .map(|share| Dealer::reshare(share, &old_signers, 3, 4).unwrap())
+            .collect();
+        let packages: Vec<_> = dealers.iter().map(|d| d.package().clone()).collect();
+        let verification_shares = [
+            old_shares[0].keypair.public_key,
+            old_shares[2].keypair.public_key,
+        ];
+
+        let new_shares: Vec<_> = (1..=4)
+            .map(|index| {
+                let dealt: Vec<_> = dealers
+                    .iter()
+                    .map(|dealer| dealer.share_for(index).unwrap())
+                    .collect();
+                reshare_finalize(
+                    index,
+                    3,
+                    keypair.public_key,
+                    &packages,
+                    &verification_shares,
+                    &dealt,
+                )
+                .unwrap()
+            })
+            .collect();
+
+        assert_eq!(secret_of(&new_shares[..3]), keypair.private_key);
+        assert_eq!(secret_of(&new_shares[1..]), secret_of(&old_shares[..2]));
+        for share in &new_shares {
+            assert_eq!(
+                reshare_verification_share(share.index, &packages),
+                share.keypair.public_key
+            );
+        }
+        assert_ne!(secret_of(&new_shares[..2]), keypair.private_key);
+
+        // A dealer that does not deal its real share is caught by its commitment.
+        assert_eq!(
+            packages[0].verify_reshare(old_shares[1].keypair.public_key, &old_signers, 3),
+            Err(SchnorrError::InvalidShare(1))
+        );
+
+        // A single old signer below the old threshold cannot reach the group key.
+        let alone = Dealer::reshare(&old_shares[0], &[1], 3, 4).unwrap();
+        assert_eq!(
+            reshare_finalize(
+                1,
+                3,
+                keypair.public_key,
+                &[alone.package().clone()],
+                &verification_shares[..1],
+                &[alone.share_for(1).unwrap()],
+            )
+            .err(),
+            Some(SchnorrError::GroupKeyMismatch)
+        );
+    }
+
+    #[test]
+    fn test_refresh_share_rejects_malformed_packages() {
+        let keypair = Keypair::<F, G>::generate();
+        let old_shares = split_keypair(&keypair, 2, 3).unwrap();
+        let dealers: Vec<_> = (1..=3)
+            .map(|index| Dealer::<F, G>::refresh(index, 2, 3).unwrap())
+            .collect();
+        let packages: Vec<_> = dealers.iter().map(|d| d.package().clone()).collect();
+        let dealt: Vec<_> = dealers.iter().map(|d| d.share_for(1).unwrap()).collect();
+
+        // A dealer shifting the constant term would change the group secret.
+        let mut shifted = packages.clone();
+        shifted[1].commitments[0] = keypair.public_key;
+        assert_eq!(
+            refresh_share(&old_shares[0], 2, &shifted, &dealt).err(),
+            Some(SchnorrError::InvalidShare(2))
+        );
+
+        let mut longer = packages.clone();
+        longer[2].commitments.push(keypair.public_key);
+        assert_eq!(
+            refresh_share(&old_shares[0], 2, &longer, &dealt).err(),
+            Some(SchnorrError::LengthMismatch {
+                expected: 2,
+                found: 3
+            })
+        );
+
+        let repeated = [packages[0].clone(), packages[0].clone()];
+        assert_eq!(
+            refresh_share(&old_shares[0], 2, &repeated, &dealt).err(),
+            Some(SchnorrError::InvalidParticipantIndex(1))
+        );
+        assert_eq!(
+            refresh_share(&old_shares[0], 0, &packages, &dealt).err(),
+            Some(SchnorrError::InvalidThreshold {
+                threshold: 0,
+                participants: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_reshare_finalize_rejects_malformed_packages() {
+        let keypair = Keypair::<F, G>::generate();
+        let old_shares = split_keypair(&keypair, 2, 3).unwrap();
+        let old_signers = [1, 2];
+        let verification_shares = [
+            old_shares[0].keypair.public_key,
+            old_shares[1].keypair.public_key,
+        ];
+
+        // A dealer using a higher threshold than agreed is rejected.
+        let dealers = [
+            Dealer::reshare(&old_shares[0], &old_signers, 2, 3).unwrap(),
+            Dealer::reshare(&old_shares[1], &old_signers, 3, 3).unwrap(),
+        ];
+        let packages: Vec<_> = dealers.iter().map(|d| d.package().clone()).collect();
+        let dealt: Vec<_> = dealers.iter().map(|d| d.share_for(1).unwrap()).collect();
+        assert_eq!(
+            reshare_finalize(
+                1,
+                2,
+                keypair.public_key,
+                &packages,
+                &verification_shares,
+                &dealt
+            )
+            .err(),
+            Some(SchnorrError::LengthMismatch {
+                expected: 2,
+                found: 3
+            })
+        );
+
+        let repeated = [packages[0].clone(), packages[0].clone()];
+        assert_eq!(
+            reshare_finalize(
+                1,
+                2,
+                keypair.public_key,
+                &repeated,
+                &verification_shares,
+                &dealt
+            )
+            .err(),
+            Some(SchnorrError::InvalidParticipantIndex(1))
+        );
+        assert_eq!(
+            reshare_finalize(
+                1,
+                2,
+                keypair.public_key,
+                &packages,
+                &verification_shares[..1],
+                &dealt
+            )
+            .err(),
+            Some(SchnorrError::LengthMismatch {
+                expected: 2,
+                found: 1
+            })
+        );
+    }
+}
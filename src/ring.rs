@@ -0,0 +1,320 @@
+//! AOS ring signatures with an optional linkable variant.
+//!
+//! Inspired by:
+//! - Abe, Ohkubo, Suzuki, "1-out-of-n Signatures from a Variety of Keys" (Asiacrypt '02)
+//! - Liu, Wei, Wong, "Linkable Spontaneous Anonymous Group Signature" (ACISP '04)
+//!
+//! A signature over the ring `P_0, ..., P_{n-1}` is `(c_0, s_0, ..., s_{n-1})`.
+//! Verification walks the ring, computing `R_i = s_i·G - c_i·P_i` and
+//! `c_{i+1} = H(ring, message, R_i)`, and accepts if the chain closes with
+//! `c_n == c_0`. The signer closes it at its own position with
+//! `s_π = α + c_π·x_π`, so the signature does not reveal which key signed.
+//! Every link hashes a Poseidon transcript that already absorbed the ring and
+//! the message.
+//!
+//! The linkable variant also publishes the key image `I = x·H_p(P)`, where
+//! `H_p` hashes a public key to a curve point by try-and-increment, and adds
+//! `R'_i = s_i·H_p(P_i) - c_i·I` to every link. Two signatures by the same
+//! key carry the same key image whatever the ring or message.
+
+#![allow(non_snake_case)]
+
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use rand::rngs::OsRng;
+
+use crate::{
+    encoding,
+    error::SchnorrError,
+    keypair::Keypair,
+    transcript::{Message, PoseidonTranscript, Transcript, poseidon_transcript},
+};
+
+/// Domain separator absorbed before the ring ("ring" in ASCII).
+const RING_TAG: u64 = 0x72696e67;
+
+/// Domain separator for hashing a public key to a point ("keyimage" in ASCII).
+const KEY_IMAGE_TAG: u64 = 0x6b6579696d616765;
+
+/// A ring signature; `key_image` is set for the linkable variant.
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct RingSignature<F: Field, G: CurveGroup> {
+    pub key_image: Option<G>,
+    pub c: F,
+    pub s: Vec<F>,
+}
+
+/// Computes the key image `x·H_p(P)` that links signatures by `keypair`.
+pub fn key_image<F: Field, G: CurveGroup<ScalarField = F>>(
+    keypair: &Keypair<F, G>,
+) -> Result<G, SchnorrError> {
+    Ok(hash_to_curve::<F, G>(keypair.public_key)? * keypair.private_key)
+}
+
+/// Hashes a public key to a non-identity point by try-and-increment.
+fn hash_to_curve<F: Field, G: CurveGroup<ScalarField = F>>(
+    public_key: G,
+) -> Result<G, SchnorrError> {
+    for counter in 0u64.. {
+        let mut hash_transcript = poseidon_transcript::<F, G>();
+        hash_transcript.absorb_scalar(F::from(KEY_IMAGE_TAG));
+        hash_transcript.absorb_point(public_key)?;
+        hash_transcript.absorb_scalar(F::from(counter));
+
+        let mut bytes = Vec::new();
+        for _ in 0..2 {
+            hash_transcript
+                .squeeze_challenge()
+                .serialize_compressed(&mut bytes)
+                .expect("serializing into a Vec cannot fail");
+        }
+        if let Some(point) = G::Affine::from_random_bytes(&bytes) {
+            let point = point.mul_by_cofactor_to_group();
+            if !point.is_zero() {
+                return Ok(point);
+            }
+        }
+    }
+    unreachable!("try-and-increment finds a point long before the counter wraps")
+}
+
+/// The shared transcript prefix and per-key hash points of one ring.
+struct RingHasher<F: Field, G: CurveGroup> {
+    prefix: PoseidonTranscript<F>,
+    key_image: Option<G>,
+    hashed_keys: Vec<G>,
+}
+
+impl<F: Field, G: CurveGroup<ScalarField = F>> RingHasher<F, G> {
+    fn new(
+        ring: &[G],
+        key_image: Option<G>,
+        message: Message<'_, F>,
+    ) -> Result<Self, SchnorrError> {
+        if ring.is_empty() {
+            return Err(SchnorrError::EmptyRing);
+        }
+        if ring.iter().any(|public_key| public_key.is_zero()) {
+            return Err(SchnorrError::IdentityPublicKey);
+        }
+
+        let mut prefix = PoseidonTranscript::new();
+        Transcript::<F, G>::absorb_scalar(&mut prefix, F::from(RING_TAG));
+        Transcript::<F, G>::absorb_scalar(&mut prefix, F::from(ring.len() as u64));
+        for public_key in ring {
+            prefix.absorb_point(*public_key)?;
+        }
+        let hashed_keys = match key_image {
+            Some(key_image) => {
+                prefix.absorb_point(key_image)?;
+                ring.iter()
+                    .map(|public_key| hash_to_curve::<F, G>(*public_key))
+                    .collect::<Result<_, _>>()?
+            }
+            None => Vec::new(),
+        };
+        Transcript::<F, G>::absorb_message(&mut prefix, message);
+
+        Ok(RingHasher {
+            prefix,
+            key_image,
+            hashed_keys,
+        })
+    }
+
+    /// Computes `c_{i+1}` from the link at position `i` opened with `s·G` and `s·H_p(P_i)`.
+    fn link(&self, position: usize, s: F, c: F, public_key: G) -> Result<F, SchnorrError> {
+        let mut transcript = self.prefix.clone();
+        transcript.absorb_point(G::generator() * s - public_key * c)?;
+        if let Some(key_image) = self.key_image {
+            transcript.absorb_point(self.hashed_keys[position] * s - key_image * c)?;
+        }
+
+        Ok(Transcript::<F, G>::squeeze_challenge(&mut transcript))
+    }
+}
+
+impl<F: Field, G: CurveGroup<ScalarField = F>> RingSignature<F, G> {
+    /// Encodes the signature as key image, `c` and the length-prefixed `s_i`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encoding::to_bytes(self)
+    }
+
+    /// Decodes a signature produced by `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SchnorrError> {
+        Ok(encoding::from_bytes(bytes)?)
+    }
+
+    /// Signs `message` on behalf of `ring`, which must contain the signer's key.
+    pub fn sign(
+        keypair: &Keypair<F, G>,
+        ring: &[G],
+        message: Message<'_, F>,
+    ) -> Result<Self, SchnorrError> {
+        Self::sign_ring(keypair, ring, None, message)
+    }
+
+    /// Signs like `sign`, attaching the signer's key image.
+    pub fn sign_linkable(
+        keypair: &Keypair<F, G>,
+        ring: &[G],
+        message: Message<'_, F>,
+    ) -> Result<Self, SchnorrError> {
+        Self::sign_ring(keypair, ring, Some(key_image(keypair)?), message)
+    }
+
+    fn sign_ring(
+        keypair: &Keypair<F, G>,
+        ring: &[G],
+        key_image: Option<G>,
+        message: Message<'_, F>,
+    ) -> Result<Self, SchnorrError> {
+        let hasher = RingHasher::new(ring, key_image, message)?;
+        let signer = ring
+            .iter()
+            .position(|public_key| *public_key == keypair.public_key)
+            .ok_or(SchnorrError::SignerNotInKeySet)?;
+
+        let mut rng = OsRng;
+        let n = ring.len();
+        let mut c = vec![F::ZERO; n];
+        let mut s: Vec<F> = (0..n).map(|_| F::rand(&mut rng)).collect();
+
+        // Opening the signer's link with c = 0 gives R = α·G and R' = α·H_p(P).
+        let alpha = s[signer];
+        c[(signer + 1) % n] = hasher.link(signer, alpha, F::ZERO, keypair.public_key)?;
+        for offset in 1..n {
+            let i = (signer + offset) % n;
+            c[(i + 1) % n] = hasher.link(i, s[i], c[i], ring[i])?;
+        }
+        s[signer] = alpha + c[signer] * keypair.private_key;
+
+        Ok(RingSignature {
+            key_image,
+            c: c[0],
+            s,
+        })
+    }
+
+    /// Verifies the signature for `message` against `ring`.
+    ///
+    /// Fails with `LengthMismatch` if the ring and responses differ in length,
+    /// `IdentityPoint` for an identity key image and `EquationMismatch` if the
+    /// ring does not close.
+    pub fn verify(&self, ring: &[G], message: Message<'_, F>) -> Result<(), SchnorrError> {
+        if self.s.len() != ring.len() {
+            return Err(SchnorrError::LengthMismatch {
+                expected: ring.len(),
+                found: self.s.len(),
+            });
+        }
+        if self.key_image.is_some_and(|key_image| key_image.is_zero()) {
+            return Err(SchnorrError::IdentityPoint);
+        }
+
+        let hasher = RingHasher::new(ring, self.key_image, message)?;
+        let mut c = self.c;
+        for (i, (s, public_key)) in self.s.iter().zip(ring).enumerate() {
+            c = hasher
+                .link(i, *s, c, *public_key)
+                .map_err(|_| SchnorrError::EquationMismatch)?;
+        }
+
+        if c != self.c {
+            return Err(SchnorrError::EquationMismatch);
+        }
+        Ok(())
+    }
+
+    /// Returns whether both signatures are linkable and were made with the same key.
+    pub fn is_linked(&self, other: &Self) -> bool {
+        matches!((self.key_image, other.key_image), (Some(a), Some(b)) if a == b)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    type F = ark_bn254::Fr;
+    type G = ark_bn254::G1Projective;
+
+    fn ring(n: usize) -> (Vec<Keypair<F, G>>, Vec<G>) {
+        let keypairs: Vec<_> = (0..n).map(|_| Keypair::<F, G>::generate()).collect();
+        let pub_keys = keypairs.iter().map(|k| k.public_key).collect();
+        (keypairs, pub_keys)
+    }
+
+    #[test]
+    fn test_ring_signature_valid() {
+        let (keypairs, pub_keys) = ring(4);
+        let message = Message::Bytes(b"one of us");
+
+        for keypair in &keypairs {
+            let signature = RingSignature::sign(keypair, &pub_keys, message).unwrap();
+            assert!(signature.verify(&pub_keys, message).is_ok());
+            assert_eq!(
+                signature.verify(&pub_keys, Message::Bytes(b"one of them")),
+                Err(SchnorrError::EquationMismatch)
+            );
+
+            let bytes = signature.to_bytes();
+            assert_eq!(RingSignature::from_bytes(&bytes).unwrap(), signature);
+        }
+
+        let signature = RingSignature::sign(&keypairs[0], &pub_keys, message).unwrap();
+        let mut other_ring = pub_keys.clone();
+        other_ring[3] = Keypair::<F, G>::generate().public_key;
+        assert_eq!(
+            signature.verify(&other_ring, message),
+            Err(SchnorrError::EquationMismatch)
+        );
+        assert_eq!(
+            signature.verify(&pub_keys[..3], message),
+            Err(SchnorrError::LengthMismatch {
+                expected: 3,
+                found: 4
+            })
+        );
+        assert_eq!(
+            RingSignature::sign(&Keypair::generate(), &pub_keys, message),
+            Err(SchnorrError::SignerNotInKeySet)
+        );
+    }
+
+    #[test]
+    fn test_linkable_ring_signature() {
+        let (keypairs, pub_keys) = ring(3);
+        let (_, other_ring) = ring(2);
+        let mut other_ring = other_ring;
+        other_ring.push(keypairs[1].public_key);
+
+        let first =
+            RingSignature::sign_linkable(&keypairs[1], &pub_keys, Message::Scalar(F::from(1)))
+                .unwrap();
+        let second =
+            RingSignature::sign_linkable(&keypairs[1], &other_ring, Message::Scalar(F::from(2)))
+                .unwrap();
+        let third =
+            RingSignature::sign_linkable(&keypairs[2], &pub_keys, Message::Scalar(F::from(1)))
+                .unwrap();
+
+        assert!(first.verify(&pub_keys, Message::Scalar(F::from(1))).is_ok());
+        assert!(
+            second
+                .verify(&other_ring, Message::Scalar(F::from(2)))
+                .is_ok()
+        );
+        assert!(first.is_linked(&second));
+        assert!(!first.is_linked(&third));
+
+        // A key image that does not belong to the signer breaks the ring.
+        let mut forged = first.clone();
+        forged.key_image = third.key_image;
+        assert_eq!(
+            forged.verify(&pub_keys, Message::Scalar(F::from(1))),
+            Err(SchnorrError::EquationMismatch)
+        );
+    }
+}
@@ -0,0 +1,419 @@
+//! Stateful MuSig signing session with nonce-reuse protection.
+//!
+//! `SignerSession` wraps the three-round MuSig flow from `musig` in a typestate
+//! machine over a shared `KeyAggContext`. Each step consumes the session and
+//! returns it in the next state:
+//!
+//! `KeyAgg` → `commit_nonce` → `NonceCommitted` → `reveal_nonce` →
+//! `NonceRevealed` → `partial_sign` → `PartialSigned` → `aggregate`
+//!
+//! The secret nonce lives only inside the session, is never cloned or exposed,
+//! and is dropped by `partial_sign`. A nonce therefore cannot be used for two
+//! partial signatures, and steps cannot be run out of order. The `Debug`
+//! output of the states omits the secret nonce.
+
+#![allow(non_snake_case)]
+
+use std::fmt;
+
+use ark_ec::CurveGroup;
+use ark_ff::Field;
+
+use crate::{
+    error::SchnorrError,
+    key_agg::KeyAggContext,
+    keypair::Keypair,
+    musig::{MuSig, NonceCommitment},
+    transcript::Message,
+};
+
+/// Keys are aggregated; no nonce has been generated yet.
+#[derive(Debug, Clone)]
+pub struct KeyAgg;
+
+/// The secret nonce exists and only its commitment has been shared.
+pub struct NonceCommitted<F: Field, G: CurveGroup> {
+    r: F,
+    R: G,
+    commitment: NonceCommitment<F>,
+}
+
+impl<F: Field, G: CurveGroup> fmt::Debug for NonceCommitted<F, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NonceCommitted")
+            .field("R", &self.R)
+            .field("commitment", &self.commitment)
+            .finish_non_exhaustive()
+    }
+}
+
+/// All commitments are collected and the public nonce has been revealed.
+pub struct NonceRevealed<F: Field, G: CurveGroup> {
+    r: F,
+    R: G,
+    commitments: Vec<NonceCommitment<F>>,
+}
+
+impl<F: Field, G: CurveGroup> fmt::Debug for NonceRevealed<F, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NonceRevealed")
+            .field("R", &self.R)
+            .field("commitments", &self.commitments)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The partial signature has been produced and the secret nonce is gone.
+#[derive(Debug, Clone)]
+pub struct PartialSigned<F: Field, G: CurveGroup> {
+    message: F,
+    revealed: Vec<G>,
+    partial_sig: F,
+}
+
+/// A single signer's view of a MuSig signing session in state `S`.
+#[derive(Debug)]
+pub struct SignerSession<F: Field, G: CurveGroup, S> {
+    keypair: Keypair<F, G>,
+    key_agg: KeyAggContext<F, G>,
+    signer_index: usize,
+    state: S,
+}
+
+impl<F: Field, G: CurveGroup<ScalarField = F>, S> SignerSession<F, G, S> {
+    /// Returns the aggregated public key of the session.
+    pub fn agg_pub_key(&self) -> G {
+        self.key_agg.agg_pub_key()
+    }
+
+    /// Moves the session into the next state.
+    fn transition<T>(self, state: T) -> SignerSession<F, G, T> {
+        SignerSession {
+            keypair: self.keypair,
+            key_agg: self.key_agg,
+            signer_index: self.signer_index,
+            state,
+        }
+    }
+}
+
+impl<F: Field, G: CurveGroup<ScalarField = F>> SignerSession<F, G, KeyAgg> {
+    /// Starts a session for a group aggregated in `key_agg`, which must contain
+    /// the signer's key. The same context can back any number of sessions.
+    ///
+    /// Fails with `DuplicatePublicKey` holding the second position if the key
+    /// occurs more than once, since the signer's position is then ambiguous;
+    /// start one session per occurrence with `with_index` instead.
+    pub fn new(keypair: Keypair<F, G>, key_agg: KeyAggContext<F, G>) -> Result<Self, SchnorrError> {
+        let mut positions = key_agg
+            .pub_keys()
+            .iter()
+            .enumerate()
+            .filter(|(_, key)| **key == keypair.public_key)
+            .map(|(index, _)| index);
+        let signer_index = positions.next().ok_or(SchnorrError::SignerNotInKeySet)?;
+        if let Some(repeat) = positions.next() {
+            return Err(SchnorrError::DuplicatePublicKey(repeat));
+        }
+
+        Self::with_index(keypair, key_agg, signer_index)
+    }
+
+    /// Starts a session for the signer at position `signer_index` of `key_agg`.
+    ///
+    /// Fails with `SignerNotInKeySet` if the key at that position is not the
+    /// signer's.
+    pub fn with_index(
+        keypair: Keypair<F, G>,
+        key_agg: KeyAggContext<F, G>,
+        signer_index: usize,
+    ) -> Result<Self, SchnorrError> {
+        if key_agg.pub_keys().get(signer_index) != Some(&keypair.public_key) {
+            return Err(SchnorrError::SignerNotInKeySet);
+        }
+
+        Ok(SignerSession {
+            keypair,
+            key_agg,
+            signer_index,
+            state: KeyAgg,
+        })
+    }
+
+    /// Generates the secret nonce; broadcast `commitment()` of the returned session.
+    pub fn commit_nonce(self) -> Result<SignerSession<F, G, NonceCommitted<F, G>>, SchnorrError> {
+        let (r, R, commitment) = MuSig::<F, G>::create_committed_nonce()?;
+
+        Ok(self.transition(NonceCommitted { r, R, commitment }))
+    }
+}
+
+impl<F: Field, G: CurveGroup<ScalarField = F>> SignerSession<F, G, NonceCommitted<F, G>> {
+    /// Returns the nonce commitment to send to the other signers.
+    pub fn commitment(&self) -> NonceCommitment<F> {
+        self.state.commitment
+    }
+
+    /// Records every participant's commitment; broadcast `nonce()` of the returned session.
+    ///
+    /// `commitments` must be ordered like the session's public keys and contain
+    /// this signer's own commitment at its position.
+    pub fn reveal_nonce(
+        self,
+        commitments: Vec<NonceCommitment<F>>,
+    ) -> Result<SignerSession<F, G, NonceRevealed<F, G>>, SchnorrError> {
+        let signers = self.key_agg.pub_keys().len();
+        if commitments.len() != signers {
+            return Err(SchnorrError::LengthMismatch {
+                expected: signers,
+                found: commitments.len(),
+            });
+        }
+        if commitments[self.signer_index] != self.state.commitment {
+            return Err(SchnorrError::NonceCommitmentMismatch(vec![
+                self.signer_index,
+            ]));
+        }
+
+        let NonceCommitted { r, R, .. } = self.state;
+        Ok(self.transition(NonceRevealed { r, R, commitments }))
+    }
+}
+
+impl<F: Field, G: CurveGroup<ScalarField = F>> SignerSession<F, G, NonceRevealed<F, G>> {
+    /// Returns the public nonce `R` to reveal to the other signers.
+    pub fn nonce(&self) -> G {
+        self.state.R
+    }
+
+    /// Checks all revealed nonces against their commitments and signs `message`;
+    /// broadcast `partial_sig()` of the returned session.
+    ///
+    /// The secret nonce is consumed whether or not signing succeeds, so a
+    /// failed session has to be restarted from `SignerSession::new`.
+    pub fn partial_sign(
+        self,
+        message: F,
+        revealed: &[G],
+    ) -> Result<SignerSession<F, G, PartialSigned<F, G>>, SchnorrError> {
+        let NonceRevealed { r, R, commitments } = &self.state;
+        if revealed.get(self.signer_index) != Some(R) {
+            return Err(SchnorrError::NonceCommitmentMismatch(vec![
+                self.signer_index,
+            ]));
+        }
+
+        let r = *r;
+        let agg_R = MuSig::<F, G>::agg_revealed_nonces(commitments, revealed)?;
+        let partial_sig = MuSig::sign_with_context(
+            &self.keypair,
+            &self.key_agg,
+            Message::Scalar(message),
+            agg_R,
+            r,
+        )?;
+
+        Ok(self.transition(PartialSigned {
+            message,
+            revealed: revealed.to_vec(),
+            partial_sig,
+        }))
+    }
+}
+
+impl<F: Field, G: CurveGroup<ScalarField = F>> SignerSession<F, G, PartialSigned<F, G>> {
+    /// Returns this signer's partial signature.
+    pub fn partial_sig(&self) -> F {
+        self.state.partial_sig
+    }
+
+    /// Returns the message the session signed.
+    pub fn message(&self) -> F {
+        self.state.message
+    }
+
+    /// Verifies all participants' partial signatures and combines them.
+    ///
+    /// Fails with `InvalidPartialSignatures` naming the signers whose
+    /// contribution does not verify.
+    pub fn aggregate(self, partial_sigs: &[F]) -> Result<MuSig<F, G>, SchnorrError> {
+        MuSig::aggregate(
+            partial_sigs,
+            &self.state.revealed,
+            &self.key_agg,
+            Message::Scalar(self.state.message),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::transcript::PoseidonTranscript;
+    use ark_ec::PrimeGroup;
+
+    type F = ark_bn254::Fr;
+    type G = ark_bn254::G1Projective;
+
+    /// Starts `n` sessions over the same key set and runs them to the reveal round.
+    fn revealed_sessions(n: usize) -> Vec<SignerSession<F, G, NonceRevealed<F, G>>> {
+        let keypairs: Vec<_> = (0..n).map(|_| Keypair::<F, G>::generate()).collect();
+        let pub_keys: Vec<_> = keypairs.iter().map(|k| k.public_key).collect();
+        let key_agg = KeyAggContext::new(&pub_keys).unwrap();
+
+        let sessions: Vec<_> = keypairs
+            .into_iter()
+            .map(|keypair| {
+                SignerSession::new(keypair, key_agg.clone())
+                    .unwrap()
+                    .commit_nonce()
+                    .unwrap()
+            })
+            .collect();
+        let commitments: Vec<_> = sessions.iter().map(|s| s.commitment()).collect();
+
+        sessions
+            .into_iter()
+            .map(|session| session.reveal_nonce(commitments.clone()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_signer_session_valid() {
+        let message = F::from(16);
+        let sessions = revealed_sessions(3);
+        let revealed: Vec<_> = sessions.iter().map(|s| s.nonce()).collect();
+
+        let sessions: Vec<_> = sessions
+            .into_iter()
+            .map(|session| session.partial_sign(message, &revealed).unwrap())
+            .collect();
+        let partial_sigs: Vec<_> = sessions.iter().map(|s| s.partial_sig()).collect();
+
+        let session = sessions.into_iter().next().unwrap();
+        let agg_pub_key = session.agg_pub_key();
+        assert_eq!(session.message(), message);
+        let musig = session.aggregate(&partial_sigs).unwrap();
+
+        let mut transcript = PoseidonTranscript::new();
+        assert!(musig.verify(agg_pub_key, &mut transcript, message).is_ok());
+    }
+
+    #[test]
+    fn test_signer_session_rejects_changed_nonce() {
+        let mut sessions = revealed_sessions(2);
+        let mut revealed: Vec<_> = sessions.iter().map(|s| s.nonce()).collect();
+
+        // The second signer swaps its nonce after seeing the first one.
+        revealed[1] += G::generator();
+        let session = sessions.remove(0);
+        assert_eq!(
+            session.partial_sign(F::from(16), &revealed).err(),
+            Some(SchnorrError::NonceCommitmentMismatch(vec![1]))
+        );
+    }
+
+    #[test]
+    fn test_signer_session_names_bad_partial_signature() {
+        let message = F::from(16);
+        let sessions = revealed_sessions(3);
+        let revealed: Vec<_> = sessions.iter().map(|s| s.nonce()).collect();
+
+        let sessions: Vec<_> = sessions
+            .into_iter()
+            .map(|session| session.partial_sign(message, &revealed).unwrap())
+            .collect();
+        let mut partial_sigs: Vec<_> = sessions.iter().map(|s| s.partial_sig()).collect();
+        partial_sigs[2] += F::from(1);
+
+        let session = sessions.into_iter().next().unwrap();
+        assert_eq!(
+            session.aggregate(&partial_sigs).err(),
+            Some(SchnorrError::InvalidPartialSignatures(vec![2]))
+        );
+    }
+
+    #[test]
+    fn test_signer_session_debug_omits_secrets() {
+        let session = revealed_sessions(2).remove(0);
+        let debug = format!("{session:?}");
+
+        assert!(!debug.contains(&format!("{:?}", session.state.r)));
+        assert!(!debug.contains(&format!("{:?}", session.keypair.private_key)));
+        assert!(debug.contains(&format!("{:?}", session.state.R)));
+    }
+
+    #[test]
+    fn test_signer_session_duplicate_keys() {
+        use crate::key_agg::{DuplicateKeys, KeyAggOptions};
+
+        let message = F::from(16);
+        let a = Keypair::<F, G>::generate();
+        let b = Keypair::<F, G>::generate();
+        let keypairs = [a.clone(), b.clone(), b, a];
+        let pub_keys: Vec<_> = keypairs.iter().map(|k| k.public_key).collect();
+
+        for duplicates in [DuplicateKeys::Allow, DuplicateKeys::SecondUniqueKey] {
+            let options = KeyAggOptions {
+                sort: false,
+                duplicates,
+            };
+            let key_agg = KeyAggContext::with_options(&pub_keys, options).unwrap();
+            assert_eq!(
+                SignerSession::new(keypairs[0].clone(), key_agg.clone()).err(),
+                Some(SchnorrError::DuplicatePublicKey(3))
+            );
+            assert_eq!(
+                SignerSession::with_index(keypairs[0].clone(), key_agg.clone(), 1).err(),
+                Some(SchnorrError::SignerNotInKeySet)
+            );
+
+            // One session per occurrence, each with its own nonce.
+            let sessions: Vec<_> = keypairs
+                .iter()
+                .enumerate()
+                .map(|(index, keypair)| {
+                    SignerSession::with_index(keypair.clone(), key_agg.clone(), index)
+                        .unwrap()
+                        .commit_nonce()
+                        .unwrap()
+                })
+                .collect();
+            let commitments: Vec<_> = sessions.iter().map(|s| s.commitment()).collect();
+            let sessions: Vec<_> = sessions
+                .into_iter()
+                .map(|session| session.reveal_nonce(commitments.clone()).unwrap())
+                .collect();
+            let revealed: Vec<_> = sessions.iter().map(|s| s.nonce()).collect();
+            let sessions: Vec<_> = sessions
+                .into_iter()
+                .map(|session| session.partial_sign(message, &revealed).unwrap())
+                .collect();
+            let partial_sigs: Vec<_> = sessions.iter().map(|s| s.partial_sig()).collect();
+
+            let musig = sessions
+                .into_iter()
+                .next()
+                .unwrap()
+                .aggregate(&partial_sigs)
+                .unwrap();
+            let mut transcript = PoseidonTranscript::new();
+            assert!(
+                musig
+                    .verify(key_agg.agg_pub_key(), &mut transcript, message)
+                    .is_ok()
+            );
+        }
+    }
+
+    #[test]
+    fn test_signer_session_rejects_outsider() {
+        let keypair = Keypair::<F, G>::generate();
+        let other = Keypair::<F, G>::generate();
+
+        assert_eq!(
+            SignerSession::new(keypair, KeyAggContext::new(&[other.public_key]).unwrap()).err(),
+            Some(SchnorrError::SignerNotInKeySet)
+        );
+    }
+}
@@ -0,0 +1,230 @@
+//! Shamir secret sharing over the scalar field.
+//!
+//! A secret `s` is the constant term of a random polynomial `f` of degree
+//! `threshold - 1`; participant `i` (a non-zero index) receives `f(i)`. Any
+//! `threshold` shares recover `s` by Lagrange interpolation at zero, while
+//! fewer reveal nothing about it.
+//!
+//! Feldman commitments `C_k = a_k·G` to the coefficients let every participant
+//! check its share without learning the polynomial: `f(i)·G == Σ C_k·i^k`.
+
+use ark_ec::CurveGroup;
+use ark_ff::Field;
+use rand::rngs::OsRng;
+use std::fmt;
+
+use crate::error::SchnorrError;
+
+/// A polynomial over `F`, stored as coefficients from the constant term up.
+///
+/// Its `Debug` output omits the coefficients.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Polynomial<F: Field> {
+    coefficients: Vec<F>,
+}
+
+/// One participant's evaluation `f(index)` of a sharing polynomial.
+///
+/// Its `Debug` output shows the index only.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct SecretShare<F: Field> {
+    pub index: u32,
+    pub value: F,
+}
+
+impl<F: Field> fmt::Debug for Polynomial<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Polynomial").finish_non_exhaustive()
+    }
+}
+
+impl<F: Field> fmt::Debug for SecretShare<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecretShare")
+            .field("index", &self.index)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F: Field> Polynomial<F> {
+    /// Samples a random polynomial of the given degree with constant term `constant`.
+    pub fn random(constant: F, degree: usize) -> Self {
+        let mut rng = OsRng;
+        let coefficients = std::iter::once(constant)
+            .chain((0..degree).map(|_| F::rand(&mut rng)))
+            .collect();
+
+        Polynomial { coefficients }
+    }
+
+    /// Returns the coefficients, constant term first.
+    pub fn coefficients(&self) -> &[F] {
+        &self.coefficients
+    }
+
+    /// Evaluates the polynomial at `x` with Horner's rule.
+    pub fn evaluate(&self, x: F) -> F {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(F::ZERO, |acc, coefficient| acc * x + coefficient)
+    }
+
+    /// Returns the Feldman commitments `a_k·G` to every coefficient.
+    pub fn commitments<G: CurveGroup<ScalarField = F>>(&self) -> Vec<G> {
+        self.coefficients
+            .iter()
+            .map(|coefficient| G::generator() * coefficient)
+            .collect()
+    }
+
+    /// Returns the shares `f(1), ..., f(participants)`.
+    pub fn shares(&self, participants: u32) -> Vec<SecretShare<F>> {
+        (1..=participants)
+            .map(|index| SecretShare {
+                index,
+                value: self.evaluate(F::from(index)),
+            })
+            .collect()
+    }
+}
+
+/// Evaluates committed coefficients at `index`, giving `f(index)·G`.
+pub fn evaluate_commitments<F: Field, G: CurveGroup<ScalarField = F>>(
+    commitments: &[G],
+    index: u32,
+) -> G {
+    let x = F::from(index);
+    commitments
+        .iter()
+        .rev()
+        .fold(G::zero(), |acc, commitment| acc * x + commitment)
+}
+
+/// Checks a share against Feldman commitments to the dealer's polynomial.
+pub fn verify_share<F: Field, G: CurveGroup<ScalarField = F>>(
+    share: &SecretShare<F>,
+    commitments: &[G],
+) -> bool {
+    G::generator() * share.value == evaluate_commitments(commitments, share.index)
+}
+
+/// Checks that `1 <= threshold <= participants`.
+pub(crate) fn check_threshold(threshold: u32, participants: u32) -> Result<(), SchnorrError> {
+    if threshold == 0 || threshold > participants {
+        return Err(SchnorrError::InvalidThreshold {
+            threshold,
+            participants,
+        });
+    }
+    Ok(())
+}
+
+/// Splits `secret` into `participants` shares, any `threshold` of which recover it.
+pub fn split_secret<F: Field>(
+    secret: F,
+    threshold: u32,
+    participants: u32,
+) -> Result<Vec<SecretShare<F>>, SchnorrError> {
+    check_threshold(threshold, participants)?;
+
+    Ok(Polynomial::random(secret, threshold as usize - 1).shares(participants))
+}
+
+/// Computes the Lagrange coefficient of `index` for interpolating at zero
+/// from the participants in `indices`.
+///
+/// Fails with `InvalidParticipantIndex` if an index is zero or repeated, or
+/// if `index` is not in `indices`.
+pub fn lagrange_coefficient<F: Field>(index: u32, indices: &[u32]) -> Result<F, SchnorrError> {
+    check_indices(indices)?;
+    if !indices.contains(&index) {
+        return Err(SchnorrError::InvalidParticipantIndex(index));
+    }
+
+    let x_i = F::from(index);
+    let (numerator, denominator) = indices.iter().filter(|other| **other != index).fold(
+        (F::ONE, F::ONE),
+        |(num, den), other| {
+            let x_j = F::from(*other);
+            (num * x_j, den * (x_j - x_i))
+        },
+    );
+
+    Ok(numerator
+        * denominator
+            .inverse()
+            .expect("indices are distinct, so the denominator is non-zero"))
+}
+
+/// Recovers the secret from `threshold` or more shares.
+pub fn interpolate<F: Field>(shares: &[SecretShare<F>]) -> Result<F, SchnorrError> {
+    let indices: Vec<u32> = shares.iter().map(|share| share.index).collect();
+
+    shares.iter().try_fold(F::ZERO, |secret, share| {
+        Ok(secret + lagrange_coefficient::<F>(share.index, &indices)? * share.value)
+    })
+}
+
+/// Rejects zero and repeated participant indices.
+pub(crate) fn check_indices(indices: &[u32]) -> Result<(), SchnorrError> {
+    for (position, index) in indices.iter().enumerate() {
+        if *index == 0 || indices[..position].contains(index) {
+            return Err(SchnorrError::InvalidParticipantIndex(*index));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    type F = ark_bn254::Fr;
+
+    #[test]
+    fn test_shamir_any_threshold_subset_recovers() {
+        let secret = F::from(42);
+        let shares = split_secret(secret, 3, 5).unwrap();
+
+        assert_eq!(interpolate(&shares[..3]).unwrap(), secret);
+        assert_eq!(
+            interpolate(&[shares[4], shares[1], shares[3]]).unwrap(),
+            secret
+        );
+        assert_eq!(interpolate(&shares).unwrap(), secret);
+        assert_ne!(interpolate(&shares[..2]).unwrap(), secret);
+    }
+
+    #[test]
+    fn test_shamir_rejects_bad_parameters() {
+        assert_eq!(
+            split_secret(F::from(1), 4, 3),
+            Err(SchnorrError::InvalidThreshold {
+                threshold: 4,
+                participants: 3
+            })
+        );
+        assert_eq!(
+            lagrange_coefficient::<F>(1, &[1, 2, 1]),
+            Err(SchnorrError::InvalidParticipantIndex(1))
+        );
+        assert_eq!(
+            lagrange_coefficient::<F>(3, &[1, 2]),
+            Err(SchnorrError::InvalidParticipantIndex(3))
+        );
+    }
+
+    #[test]
+    fn test_feldman_commitments_check_shares() {
+        type G = ark_bn254::G1Projective;
+
+        let polynomial = Polynomial::random(F::from(42), 2);
+        let commitments: Vec<G> = polynomial.commitments();
+        let mut shares = polynomial.shares(4);
+
+        assert!(shares.iter().all(|share| verify_share(share, &commitments)));
+        shares[2].value += F::from(1);
+        assert!(!verify_share(&shares[2], &commitments));
+    }
+}
@@ -2,7 +2,11 @@
 //!
 //! Implements:
 //! - `Signature::sign`: Sign a message with a keypair
+//! - `Signature::sign_bytes` / `Signature::sign_scalars`: Sign byte strings or scalar slices
+//! - `Signature::sign_deterministic`: Sign with a nonce derived from the key and message
 //! - `Signature::verify`: Verify a signature against a message and public key
+//! - `Signature::verify_bytes` / `Signature::verify_scalars`: Verify byte or slice messages
+//! - `Signature::batch_verify`: Verify many signatures with one multi-scalar multiplication
 //!
 //! Based on classical Schnorr signatures (Claus Schnorr, Crypto '89),
 //! adapted for ZK-friendly applications with Poseidon hashing over Arkworks.
@@ -11,52 +15,271 @@
 
 use ark_ec::CurveGroup;
 use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use rand::rngs::OsRng;
 
-use crate::{keypair::Keypair, transcript::Transcript};
+use crate::{
+    encoding,
+    error::SchnorrError,
+    keypair::Keypair,
+    transcript::{Message, PoseidonTranscript, Transcript, poseidon_transcript},
+};
+
+/// Domain separator absorbed first when deriving deterministic nonces ("nonce" in ASCII).
+const NONCE_DOMAIN_TAG: u64 = 0x6e6f6e6365;
+
+/// Domain separator for nonces hedged with auxiliary randomness ("nonceaux" in ASCII).
+const HEDGED_NONCE_DOMAIN_TAG: u64 = 0x6e6f6e6365617578;
 
 /// A Schnorr signature consisting of a nonce commitment `R` and a response `s`.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
 pub struct Signature<F: Field, G: CurveGroup> {
     pub R: G,
     pub s: F,
 }
 impl<F: Field, G: CurveGroup<ScalarField = F>> Signature<F, G> {
+    /// Encodes the signature as compressed `R || s`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encoding::to_bytes(self)
+    }
+
+    /// Decodes a signature produced by `to_bytes`, validating `R` and `s`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SchnorrError> {
+        Ok(encoding::from_bytes(bytes)?)
+    }
+
     /// Signs a message using the given keypair and Poseidon transcript.
     pub fn sign(
         keypair: &Keypair<F, G>,
         transcript: &mut impl Transcript<F, G>,
         message: F,
-    ) -> Self {
+    ) -> Result<Self, SchnorrError> {
+        Self::sign_message(keypair, transcript, Message::Scalar(message))
+    }
+
+    /// Signs an arbitrary-length byte string.
+    ///
+    /// The bytes are absorbed with a length prefix and packed into limbs of
+    /// `bytes_per_limb` bytes, so the signature does not verify for any other
+    /// message encoding.
+    pub fn sign_bytes(
+        keypair: &Keypair<F, G>,
+        transcript: &mut impl Transcript<F, G>,
+        message: &[u8],
+    ) -> Result<Self, SchnorrError> {
+        Self::sign_message(keypair, transcript, Message::Bytes(message))
+    }
+
+    /// Signs a slice of field elements, absorbed with a length prefix.
+    pub fn sign_scalars(
+        keypair: &Keypair<F, G>,
+        transcript: &mut impl Transcript<F, G>,
+        message: &[F],
+    ) -> Result<Self, SchnorrError> {
+        Self::sign_message(keypair, transcript, Message::Scalars(message))
+    }
+
+    /// Signs a message in any encoding with a fresh random nonce.
+    fn sign_message(
+        keypair: &Keypair<F, G>,
+        transcript: &mut impl Transcript<F, G>,
+        message: Message<'_, F>,
+    ) -> Result<Self, SchnorrError> {
         let mut rng = OsRng;
         let r = F::rand(&mut rng);
-        let R = G::generator() * r;
 
-        transcript.absorb_point(R);
-        transcript.absorb_point(keypair.public_key);
-        transcript.absorb_scalar(message);
-        let challenge = transcript.squeeze_challenge();
+        Self::sign_with_nonce(keypair, transcript, message, r)
+    }
+
+    /// Signs a message with a nonce derived from the keypair and the message.
+    ///
+    /// Signing the same message twice with the same `aux_rand` yields the same
+    /// signature, so no RNG is needed. Passing fresh auxiliary randomness hedges
+    /// against fault attacks without making the nonce depend on it alone.
+    pub fn sign_deterministic(
+        keypair: &Keypair<F, G>,
+        transcript: &mut impl Transcript<F, G>,
+        message: F,
+        aux_rand: Option<F>,
+    ) -> Result<Self, SchnorrError> {
+        let r = Self::derive_nonce(keypair, message, aux_rand)?;
+
+        Self::sign_with_nonce(keypair, transcript, Message::Scalar(message), r)
+    }
+
+    /// Derives the nonce scalar used by `sign_deterministic`.
+    ///
+    /// The nonce is a Poseidon hash of a domain tag, the private key, the public
+    /// key, the message and, when given, the auxiliary randomness. The hedged
+    /// path uses its own domain tag, so `None` and `Some(0)` give different
+    /// nonces even though the sponge zero-pads its input.
+    pub fn derive_nonce(
+        keypair: &Keypair<F, G>,
+        message: F,
+        aux_rand: Option<F>,
+    ) -> Result<F, SchnorrError> {
+        let domain_tag = match aux_rand {
+            Some(_) => HEDGED_NONCE_DOMAIN_TAG,
+            None => NONCE_DOMAIN_TAG,
+        };
+        let mut nonce_transcript = poseidon_transcript::<F, G>();
+        nonce_transcript.absorb_scalar(F::from(domain_tag));
+        nonce_transcript.absorb_scalar(keypair.private_key);
+        nonce_transcript.absorb_point(keypair.public_key)?;
+        nonce_transcript.absorb_scalar(message);
+        if let Some(aux_rand) = aux_rand {
+            nonce_transcript.absorb_scalar(aux_rand);
+        }
+
+        Ok(nonce_transcript.squeeze_challenge())
+    }
+
+    /// Signs a message with an explicit nonce `r`.
+    fn sign_with_nonce(
+        keypair: &Keypair<F, G>,
+        transcript: &mut impl Transcript<F, G>,
+        message: Message<'_, F>,
+        r: F,
+    ) -> Result<Self, SchnorrError> {
+        let R = G::generator() * r;
+        let challenge = Self::challenge(transcript, R, keypair.public_key, message)?;
 
         let s = r + challenge * keypair.private_key;
 
-        Signature { R, s }
+        Ok(Signature { R, s })
     }
 
     /// Verifies the signature against a message and public key.
+    ///
+    /// Fails with `IdentityPublicKey` or `MalformedR` for unusable inputs and
+    /// with `EquationMismatch` when the signature does not match the message.
     pub fn verify(
         &self,
         public_key: G,
         transcript: &mut impl Transcript<F, G>,
         message: F,
-    ) -> bool {
-        transcript.absorb_point(self.R);
-        transcript.absorb_point(public_key);
-        transcript.absorb_scalar(message);
+    ) -> Result<(), SchnorrError> {
+        self.verify_message(public_key, transcript, Message::Scalar(message))
+    }
+
+    /// Verifies a signature produced by `sign_bytes`.
+    pub fn verify_bytes(
+        &self,
+        public_key: G,
+        transcript: &mut impl Transcript<F, G>,
+        message: &[u8],
+    ) -> Result<(), SchnorrError> {
+        self.verify_message(public_key, transcript, Message::Bytes(message))
+    }
+
+    /// Verifies a signature produced by `sign_scalars`.
+    pub fn verify_scalars(
+        &self,
+        public_key: G,
+        transcript: &mut impl Transcript<F, G>,
+        message: &[F],
+    ) -> Result<(), SchnorrError> {
+        self.verify_message(public_key, transcript, Message::Scalars(message))
+    }
 
-        let challenge = transcript.squeeze_challenge();
+    /// Verifies the signature against a message in any encoding.
+    fn verify_message(
+        &self,
+        public_key: G,
+        transcript: &mut impl Transcript<F, G>,
+        message: Message<'_, F>,
+    ) -> Result<(), SchnorrError> {
+        if public_key.is_zero() {
+            return Err(SchnorrError::IdentityPublicKey);
+        }
+        if self.R.is_zero() {
+            return Err(SchnorrError::MalformedR);
+        }
+
+        let challenge = Self::challenge(transcript, self.R, public_key, message)?;
         let lhs = G::generator() * self.s;
         let rhs = (public_key * challenge) + self.R;
 
-        lhs == rhs
+        if lhs != rhs {
+            return Err(SchnorrError::EquationMismatch);
+        }
+        Ok(())
+    }
+
+    /// Verifies a batch of `(public_key, message, signature)` entries at once.
+    ///
+    /// The verification equations are combined with random weights `z_i` into
+    /// a single multi-scalar multiplication
+    /// `(Σ z_i·s_i)·G - Σ z_i·R_i - Σ (z_i·c_i)·P_i == 0`,
+    /// with every challenge computed on a fresh Poseidon transcript. If the
+    /// combined check fails, each entry is verified on its own and the indices
+    /// of the invalid signatures are returned in `BatchVerification`.
+    pub fn batch_verify(entries: &[(G, F, Signature<F, G>)]) -> Result<(), SchnorrError> {
+        let mut rng = OsRng;
+        let mut bases = Vec::with_capacity(2 * entries.len() + 1);
+        let mut scalars = Vec::with_capacity(2 * entries.len() + 1);
+        let mut generator_scalar = F::ZERO;
+        let mut all_well_formed = true;
+
+        for (public_key, message, signature) in entries {
+            if public_key.is_zero() || signature.R.is_zero() {
+                all_well_formed = false;
+                continue;
+            }
+            let mut transcript = PoseidonTranscript::new();
+            let Ok(challenge) = Self::challenge(
+                &mut transcript,
+                signature.R,
+                *public_key,
+                Message::Scalar(*message),
+            ) else {
+                all_well_formed = false;
+                continue;
+            };
+            let weight = F::rand(&mut rng);
+
+            generator_scalar += weight * signature.s;
+            bases.push(signature.R);
+            scalars.push(-weight);
+            bases.push(*public_key);
+            scalars.push(-(weight * challenge));
+        }
+        bases.push(G::generator());
+        scalars.push(generator_scalar);
+
+        let bases = G::normalize_batch(&bases);
+        if all_well_formed && G::msm_unchecked(&bases, &scalars).is_zero() {
+            return Ok(());
+        }
+
+        let invalid = entries
+            .iter()
+            .enumerate()
+            .filter(|(_, (public_key, message, signature))| {
+                let mut transcript = PoseidonTranscript::new();
+                signature
+                    .verify(*public_key, &mut transcript, *message)
+                    .is_err()
+            })
+            .map(|(index, _)| index)
+            .collect();
+
+        Err(SchnorrError::BatchVerification(invalid))
+    }
+
+    /// Computes the Fiat–Shamir challenge `H(R, public_key, message)`.
+    pub(crate) fn challenge(
+        transcript: &mut impl Transcript<F, G>,
+        R: G,
+        public_key: G,
+        message: Message<'_, F>,
+    ) -> Result<F, SchnorrError> {
+        transcript
+            .absorb_point(R)
+            .map_err(|_| SchnorrError::MalformedR)?;
+        transcript.absorb_point(public_key)?;
+        transcript.absorb_message(message);
+
+        Ok(transcript.squeeze_challenge())
     }
 }
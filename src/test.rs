@@ -4,15 +4,47 @@
 //! - `test_single_signature_valid`: Validates correct signature verification
 //! - `test_single_signature_invalid`: Ensures invalid message fails verification
 //! - `test_musig_signature_valid`: Tests multi-party MuSig signature aggregation and verification
+//! - `test_musig_verifies_as_signature`: Aggregates verify through `Signature::verify`
+//! - `test_musig_committed_*`: Three-round MuSig with nonce commitments
+//! - `test_musig_partial_*`: Partial signature verification and blame on aggregation
+//! - `test_deterministic_signature_*`: Reproducibility and known answers for derived nonces
+//! - `test_batch_verify_*`: Batch verification and bad-index reporting
+//! - `test_transcript_*`: Injective point absorption and byte packing, explicit identity handling
+//!   and Poseidon2 transcripts
+//! - `test_verify_rejects_*`: Distinct verification errors for unusable inputs
+//! - `test_*_message_*`: Signing byte strings and scalar slices
+//! - `test_message_encodings_unambiguous`: No signature verifies under another message encoding
+//! - `test_*_bytes_*`: Byte encodings of signatures and keypairs, including rejection of bad input
+//! - `test_debug_redacts_secrets`: `Debug` output of secret-holding types
+//! - `test_*_tweak*`: Tweaked keypairs and tweaked MuSig aggregate keys
 
-#![allow(non_snake_case)]
+#![allow(non_snake_case, clippy::module_inception)]
 
 #[cfg(test)]
 mod test {
+    use ark_ec::{AdditiveGroup, PrimeGroup};
+    use ark_ff::{BigInteger, PrimeField, Zero};
+    use ark_serialize::CanonicalSerialize;
+
     use crate::{
-        keypair::Keypair, musig::MuSig, signature::Signature, transcript::PoseidonTranscript,
+        blind, dkg, encoding,
+        error::SchnorrError,
+        key_agg::KeyAggContext,
+        keypair::Keypair,
+        musig::MuSig,
+        musig2,
+        poseidon_hash::poseidon2::Poseidon2,
+        signature::Signature,
+        transcript::{Message, PoseidonTranscript, Transcript, bytes_per_limb, bytes_to_limbs},
     };
 
+    /// Hex encoding of the compressed serialization, used for known-answer tests.
+    fn to_hex(value: &impl CanonicalSerialize) -> String {
+        let mut bytes = Vec::new();
+        value.serialize_compressed(&mut bytes).unwrap();
+        hex::encode(bytes)
+    }
+
     /// Tests that a valid Schnorr signature verifies correctly.
     #[test]
     fn test_single_signature_valid() {
@@ -23,10 +55,14 @@ mod test {
         let keypair = Keypair::<F, G>::generate();
 
         let mut transcript = PoseidonTranscript::<F>::new();
-        let signature = Signature::sign(&keypair, &mut transcript, message);
+        let signature = Signature::sign(&keypair, &mut transcript, message).unwrap();
 
         let mut verify_transcript = PoseidonTranscript::<F>::new();
-        assert!(signature.verify(keypair.public_key, &mut verify_transcript, message))
+        assert!(
+            signature
+                .verify(keypair.public_key, &mut verify_transcript, message)
+                .is_ok()
+        )
     }
 
     /// Tests that an invalid Schnorr signature (wrong message) fails verification.
@@ -40,10 +76,13 @@ mod test {
         let keypair = Keypair::<F, G>::generate();
 
         let mut transcript = PoseidonTranscript::<F>::new();
-        let signature = Signature::sign(&keypair, &mut transcript, message);
+        let signature = Signature::sign(&keypair, &mut transcript, message).unwrap();
 
         let mut verify_transcript = PoseidonTranscript::<F>::new();
-        assert!(!signature.verify(keypair.public_key, &mut verify_transcript, bad_message));
+        assert_eq!(
+            signature.verify(keypair.public_key, &mut verify_transcript, bad_message),
+            Err(SchnorrError::EquationMismatch)
+        );
     }
 
     /// Tests that MuSig aggregated signature with two participants verifies correctly.
@@ -61,27 +100,957 @@ mod test {
         let (r2, R2) = MuSig::<F, G>::create_nonce();
 
         let pub_keys = [k1.public_key, k2.public_key];
-        let keyset_challenge = MuSig::keyset_challenge(&pub_keys);
-        let agg_pub_keys = MuSig::agg_pub_keys(&pub_keys, keyset_challenge);
+        let keyset_challenge = MuSig::keyset_challenge(&pub_keys).unwrap();
+        let agg_pub_keys = MuSig::agg_pub_keys(&pub_keys, keyset_challenge).unwrap();
 
         let agg_R = R1 + R2;
 
-        let s1 = MuSig::sign(k1, message, keyset_challenge, agg_pub_keys, agg_R, r1);
-        let s2 = MuSig::sign(k2, message, keyset_challenge, agg_pub_keys, agg_R, r2);
+        let s1 = MuSig::sign(k1, message, keyset_challenge, agg_pub_keys, agg_R, r1).unwrap();
+        let s2 = MuSig::sign(k2, message, keyset_challenge, agg_pub_keys, agg_R, r2).unwrap();
         let agg_s = s1 + s2;
 
         let musig = MuSig { agg_R, agg_s };
 
         let mut verify_transcript = PoseidonTranscript::new();
-        let is_valid = MuSig::verify(
-            &musig,
-            message,
-            &mut verify_transcript,
-            agg_pub_keys,
+        let is_valid = musig.verify(agg_pub_keys, &mut verify_transcript, message);
+
+        assert!(is_valid.is_ok(), "MuSig verification should succeed!");
+    }
+
+    /// Tests that an aggregated MuSig signature is also a valid plain `Signature`.
+    #[test]
+    fn test_musig_verifies_as_signature() {
+        type F = ark_bn254::Fr;
+        type G = ark_bn254::G1Projective;
+
+        let message = F::from(16);
+        let k1 = Keypair::<F, G>::generate();
+        let (r1, R1) = MuSig::<F, G>::create_nonce();
+        let k2 = Keypair::<F, G>::generate();
+        let (r2, R2) = MuSig::<F, G>::create_nonce();
+
+        let pub_keys = [k1.public_key, k2.public_key];
+        let keyset_challenge = MuSig::keyset_challenge(&pub_keys).unwrap();
+        let agg_pub_key = MuSig::agg_pub_keys(&pub_keys, keyset_challenge).unwrap();
+        let agg_R = R1 + R2;
+
+        let s1 = MuSig::sign(k1, message, keyset_challenge, agg_pub_key, agg_R, r1).unwrap();
+        let s2 = MuSig::sign(k2, message, keyset_challenge, agg_pub_key, agg_R, r2).unwrap();
+        let musig = MuSig {
+            agg_R,
+            agg_s: s1 + s2,
+        };
+
+        let signature: Signature<F, G> = musig.clone().into();
+        let mut verify_transcript = PoseidonTranscript::new();
+        assert!(
+            signature
+                .verify(agg_pub_key, &mut verify_transcript, message)
+                .is_ok()
+        );
+
+        let tampered = MuSig {
             agg_R,
-            agg_s,
+            agg_s: musig.agg_s + F::from(1),
+        };
+        let mut verify_transcript = PoseidonTranscript::new();
+        assert_eq!(
+            tampered.verify(agg_pub_key, &mut verify_transcript, message),
+            Err(SchnorrError::EquationMismatch)
+        );
+    }
+
+    /// Tests that partial signatures verify and that aggregation names bad signers.
+    #[test]
+    fn test_musig_partial_signatures() {
+        type F = ark_bn254::Fr;
+        type G = ark_bn254::G1Projective;
+
+        let message = F::from(16);
+        let keypairs: Vec<_> = (0..3).map(|_| Keypair::<F, G>::generate()).collect();
+        let pub_keys: Vec<_> = keypairs.iter().map(|k| k.public_key).collect();
+        let keyset_challenge = MuSig::keyset_challenge(&pub_keys).unwrap();
+        let agg_pub_key = MuSig::agg_pub_keys(&pub_keys, keyset_challenge).unwrap();
+
+        let nonces: Vec<_> = (0..3).map(|_| MuSig::<F, G>::create_nonce()).collect();
+        let revealed: Vec<_> = nonces.iter().map(|(_, R)| *R).collect();
+        let agg_R = revealed.iter().sum();
+
+        let mut partial_sigs: Vec<_> = keypairs
+            .iter()
+            .zip(&nonces)
+            .map(|(keypair, (r, _))| {
+                MuSig::sign(
+                    keypair.clone(),
+                    message,
+                    keyset_challenge,
+                    agg_pub_key,
+                    agg_R,
+                    *r,
+                )
+                .unwrap()
+            })
+            .collect();
+
+        let key_agg = KeyAggContext::new(&pub_keys).unwrap();
+        for ((partial_sig, R), pub_key) in partial_sigs.iter().zip(&revealed).zip(&pub_keys) {
+            assert!(
+                MuSig::verify_partial(
+                    *partial_sig,
+                    *R,
+                    *pub_key,
+                    &key_agg,
+                    agg_R,
+                    Message::Scalar(message),
+                )
+                .is_ok()
+            );
+        }
+        let musig =
+            MuSig::aggregate(&partial_sigs, &revealed, &key_agg, Message::Scalar(message)).unwrap();
+        let mut verify_transcript = PoseidonTranscript::new();
+        assert!(
+            musig
+                .verify(agg_pub_key, &mut verify_transcript, message)
+                .is_ok()
         );
 
-        assert!(is_valid, "MuSig verification should succeed!");
+        partial_sigs[0] += F::from(1);
+        partial_sigs[2] = partial_sigs[1];
+        assert_eq!(
+            MuSig::aggregate(&partial_sigs, &revealed, &key_agg, Message::Scalar(message)).err(),
+            Some(SchnorrError::InvalidPartialSignatures(vec![0, 2]))
+        );
+    }
+
+    /// Tests partial verification and aggregation of MuSig signatures over a byte string.
+    #[test]
+    fn test_musig_partial_bytes_message() {
+        type F = ark_bn254::Fr;
+        type G = ark_bn254::G1Projective;
+
+        let message: &[u8] = b"multi-party byte message";
+        let keypairs: Vec<_> = (0..2).map(|_| Keypair::<F, G>::generate()).collect();
+        let pub_keys: Vec<_> = keypairs.iter().map(|k| k.public_key).collect();
+        let key_agg = KeyAggContext::new(&pub_keys).unwrap();
+        let keyset_challenge = MuSig::keyset_challenge(&pub_keys).unwrap();
+
+        let nonces: Vec<_> = (0..2).map(|_| MuSig::<F, G>::create_nonce()).collect();
+        let revealed: Vec<_> = nonces.iter().map(|(_, R)| *R).collect();
+        let agg_R = revealed.iter().sum();
+        let mut partial_sigs: Vec<_> = keypairs
+            .iter()
+            .zip(&nonces)
+            .map(|(keypair, (r, _))| {
+                MuSig::sign_bytes(
+                    keypair.clone(),
+                    message,
+                    keyset_challenge,
+                    key_agg.agg_pub_key(),
+                    agg_R,
+                    *r,
+                )
+                .unwrap()
+            })
+            .collect();
+
+        assert!(
+            MuSig::verify_partial(
+                partial_sigs[1],
+                revealed[1],
+                pub_keys[1],
+                &key_agg,
+                agg_R,
+                Message::Bytes(message),
+            )
+            .is_ok()
+        );
+        let musig =
+            MuSig::aggregate(&partial_sigs, &revealed, &key_agg, Message::Bytes(message)).unwrap();
+        let mut verify_transcript = PoseidonTranscript::new();
+        assert!(
+            musig
+                .verify_bytes(key_agg.agg_pub_key(), &mut verify_transcript, message)
+                .is_ok()
+        );
+
+        partial_sigs[1] += F::from(1);
+        assert_eq!(
+            MuSig::aggregate(&partial_sigs, &revealed, &key_agg, Message::Bytes(message)).err(),
+            Some(SchnorrError::InvalidPartialSignatures(vec![1]))
+        );
+    }
+
+    /// Tests that deterministic signing is reproducible and verifies.
+    #[test]
+    fn test_deterministic_signature_reproducible() {
+        type F = ark_bn254::Fr;
+        type G = ark_bn254::G1Projective;
+
+        let message = F::from(16);
+        let keypair = Keypair::<F, G>::generate();
+
+        let mut transcript = PoseidonTranscript::<F>::new();
+        let first =
+            Signature::sign_deterministic(&keypair, &mut transcript, message, None).unwrap();
+        let mut transcript = PoseidonTranscript::<F>::new();
+        let second =
+            Signature::sign_deterministic(&keypair, &mut transcript, message, None).unwrap();
+
+        assert_eq!(first.R, second.R);
+        assert_eq!(first.s, second.s);
+
+        let mut verify_transcript = PoseidonTranscript::<F>::new();
+        assert!(
+            first
+                .verify(keypair.public_key, &mut verify_transcript, message)
+                .is_ok()
+        );
+    }
+
+    /// Tests that the nonce depends on the message and the auxiliary randomness.
+    #[test]
+    fn test_deterministic_signature_nonce_inputs() {
+        type F = ark_bn254::Fr;
+        type G = ark_bn254::G1Projective;
+
+        let keypair = Keypair::<F, G>::generate();
+
+        let base = Signature::derive_nonce(&keypair, F::from(16), None).unwrap();
+        let other_message = Signature::derive_nonce(&keypair, F::from(17), None).unwrap();
+        let with_aux = Signature::derive_nonce(&keypair, F::from(16), Some(F::from(1))).unwrap();
+        let other_aux = Signature::derive_nonce(&keypair, F::from(16), Some(F::from(2))).unwrap();
+        let zero_aux = Signature::derive_nonce(&keypair, F::from(16), Some(F::ZERO)).unwrap();
+
+        assert_ne!(base, other_message);
+        assert_ne!(base, with_aux);
+        assert_ne!(with_aux, other_aux);
+        // Zero auxiliary randomness must not fall back to the unhedged nonce.
+        assert_ne!(base, zero_aux);
+    }
+
+    /// Known-answer tests for deterministic signatures over BN254.
+    #[test]
+    fn test_deterministic_signature_known_answers() {
+        type F = ark_bn254::Fr;
+        type G = ark_bn254::G1Projective;
+
+        let keypair = Keypair::<F, G>::from_private_key(F::from(42));
+        let message = F::from(16);
+
+        let vectors = [
+            (
+                None,
+                "b8e718c99337e17c796b68243eb66733609102f029bf0dc65d1020a3e875a726",
+                "f94faf39b42260edf2c17e17d67117e02b835c499550441ccc929d5af2c65119",
+            ),
+            (
+                Some(F::from(7)),
+                "ede04735765fd7b634de5f4c9f852795000b6aaa3abe206015e921a7988f9a26",
+                "27dceb169a61083f76d8a1e14ca12a091304f806b18b42d917294710a6b7590c",
+            ),
+        ];
+
+        for (aux_rand, expected_R, expected_s) in vectors {
+            let mut transcript = PoseidonTranscript::<F>::new();
+            let signature =
+                Signature::sign_deterministic(&keypair, &mut transcript, message, aux_rand)
+                    .unwrap();
+
+            assert_eq!(to_hex(&signature.R), expected_R);
+            assert_eq!(to_hex(&signature.s), expected_s);
+        }
+    }
+
+    /// Tests that a batch of valid signatures verifies.
+    #[test]
+    fn test_batch_verify_valid() {
+        type F = ark_bn254::Fr;
+        type G = ark_bn254::G1Projective;
+
+        let entries: Vec<_> = (0..8u64)
+            .map(|i| {
+                let keypair = Keypair::<F, G>::generate();
+                let message = F::from(i);
+                let mut transcript = PoseidonTranscript::<F>::new();
+                let signature = Signature::sign(&keypair, &mut transcript, message).unwrap();
+                (keypair.public_key, message, signature)
+            })
+            .collect();
+
+        assert_eq!(Signature::batch_verify(&entries), Ok(()));
+        assert_eq!(Signature::<F, G>::batch_verify(&[]), Ok(()));
+    }
+
+    /// Tests that a failing batch reports exactly the invalid indices.
+    #[test]
+    fn test_batch_verify_reports_invalid_indices() {
+        type F = ark_bn254::Fr;
+        type G = ark_bn254::G1Projective;
+
+        let mut entries: Vec<_> = (0..6u64)
+            .map(|i| {
+                let keypair = Keypair::<F, G>::generate();
+                let message = F::from(i);
+                let mut transcript = PoseidonTranscript::<F>::new();
+                let signature = Signature::sign(&keypair, &mut transcript, message).unwrap();
+                (keypair.public_key, message, signature)
+            })
+            .collect();
+
+        entries[1].1 = F::from(666);
+        entries[4].2.s += F::from(1);
+
+        assert_eq!(
+            Signature::batch_verify(&entries),
+            Err(SchnorrError::BatchVerification(vec![1, 4]))
+        );
+    }
+
+    /// Tests that signatures, MuSig signatures and keypairs round-trip through bytes.
+    #[test]
+    fn test_bytes_roundtrip() {
+        type F = ark_bn254::Fr;
+        type G = ark_bn254::G1Projective;
+
+        let keypair = Keypair::<F, G>::generate();
+        let mut transcript = PoseidonTranscript::<F>::new();
+        let signature = Signature::sign(&keypair, &mut transcript, F::from(16)).unwrap();
+
+        let bytes = signature.to_bytes();
+        assert_eq!(bytes.len(), 64);
+        let decoded = Signature::<F, G>::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.R, signature.R);
+        assert_eq!(decoded.s, signature.s);
+
+        let musig = MuSig::<F, G> {
+            agg_R: signature.R,
+            agg_s: signature.s,
+        };
+        let decoded = MuSig::<F, G>::from_bytes(&musig.to_bytes()).unwrap();
+        assert_eq!(decoded.agg_R, musig.agg_R);
+        assert_eq!(decoded.agg_s, musig.agg_s);
+
+        let decoded = Keypair::<F, G>::from_bytes(&keypair.to_bytes()).unwrap();
+        assert_eq!(decoded.private_key, keypair.private_key);
+        assert_eq!(decoded.public_key, keypair.public_key);
+    }
+
+    /// Tests that malformed signature bytes are rejected.
+    #[test]
+    fn test_signature_bytes_rejects_invalid() {
+        type F = ark_bn254::Fr;
+        type G = ark_bn254::G1Projective;
+
+        let keypair = Keypair::<F, G>::generate();
+        let mut transcript = PoseidonTranscript::<F>::new();
+        let bytes = Signature::sign(&keypair, &mut transcript, F::from(16))
+            .unwrap()
+            .to_bytes();
+
+        // Wrong lengths.
+        assert!(Signature::<F, G>::from_bytes(&bytes[..63]).is_err());
+        assert!(Signature::<F, G>::from_bytes(&[bytes.as_slice(), &[0]].concat()).is_err());
+
+        // Non-canonical scalar: s = modulus.
+        let mut non_canonical = bytes.clone();
+        non_canonical[32..].copy_from_slice(&F::MODULUS.to_bytes_le());
+        assert!(Signature::<F, G>::from_bytes(&non_canonical).is_err());
+
+        // R with an x-coordinate that is not on the curve.
+        let x = (0u64..)
+            .map(ark_bn254::Fq::from)
+            .find(|x| ark_bn254::G1Affine::get_point_from_x_unchecked(*x, false).is_none())
+            .unwrap();
+        let mut off_curve = bytes.clone();
+        off_curve[..32].copy_from_slice(&encoding::to_bytes(&x));
+        assert!(Signature::<F, G>::from_bytes(&off_curve).is_err());
+    }
+
+    /// Tests that a keypair whose public key does not match its private key is rejected.
+    #[test]
+    fn test_keypair_bytes_rejects_mismatched_keys() {
+        type F = ark_bn254::Fr;
+        type G = ark_bn254::G1Projective;
+
+        let keypair = Keypair::<F, G>::generate();
+        let other = Keypair::<F, G>::generate();
+        let mismatched = Keypair {
+            private_key: keypair.private_key,
+            public_key: other.public_key,
+        };
+
+        assert_eq!(
+            Keypair::<F, G>::from_bytes(&mismatched.to_bytes()).err(),
+            Some(SchnorrError::InvalidEncoding)
+        );
+    }
+
+    /// Tests that `Debug` output never contains secret scalars.
+    #[test]
+    fn test_debug_redacts_secrets() {
+        type F = ark_bn254::Fr;
+        type G = ark_bn254::G1Projective;
+
+        let keypair = Keypair::<F, G>::from_private_key(F::from(123456789));
+        let debug = format!("{keypair:?}");
+        assert!(!debug.contains("123456789"));
+        assert!(debug.contains(&format!("{:?}", keypair.public_key)));
+
+        let (sec_nonce, _) = musig2::create_nonce::<F, G>();
+        assert_eq!(format!("{sec_nonce:?}"), "SecNonce { .. }");
+
+        let dealer = dkg::Dealer::<F, G>::new(1, 2, 3).unwrap();
+        assert!(format!("{dealer:?}").starts_with("Dealer { index: 1, participants: 3, package: "));
+        let share = dealer.share_for(2).unwrap();
+        assert_eq!(
+            format!("{share:?}"),
+            "DealtShare { sender: 1, receiver: 2, .. }"
+        );
+
+        let (nonces, commitment) = blind::BlindSigner::commit::<F, G>();
+        assert_eq!(format!("{nonces:?}"), "SignerNonces { .. }");
+        let session = blind::BlindingSession::new(
+            keypair.public_key,
+            commitment,
+            Message::Scalar(F::from(16)),
+        )
+        .unwrap();
+        assert!(!format!("{session:?}").contains("alpha"));
+    }
+
+    /// Tests signing and verifying byte messages of several lengths.
+    #[test]
+    fn test_byte_message_valid() {
+        type F = ark_bn254::Fr;
+        type G = ark_bn254::G1Projective;
+
+        let keypair = Keypair::<F, G>::generate();
+        let long_message = [0xabu8; 100];
+        let messages: [&[u8]; 4] = [b"", b"hello", &[0u8; 31], &long_message];
+
+        for message in messages {
+            let mut transcript = PoseidonTranscript::<F>::new();
+            let signature = Signature::sign_bytes(&keypair, &mut transcript, message).unwrap();
+
+            let mut verify_transcript = PoseidonTranscript::<F>::new();
+            assert!(
+                signature
+                    .verify_bytes(keypair.public_key, &mut verify_transcript, message)
+                    .is_ok()
+            );
+        }
+    }
+
+    /// Tests that byte messages are length-prefixed and not confused with other encodings.
+    #[test]
+    fn test_byte_message_unambiguous() {
+        type F = ark_bn254::Fr;
+        type G = ark_bn254::G1Projective;
+
+        let keypair = Keypair::<F, G>::generate();
+        let message = b"hello";
+
+        let mut transcript = PoseidonTranscript::<F>::new();
+        let signature = Signature::sign_bytes(&keypair, &mut transcript, message).unwrap();
+
+        let mut verify_transcript = PoseidonTranscript::<F>::new();
+        assert_eq!(
+            signature.verify_bytes(keypair.public_key, &mut verify_transcript, b"hello\0"),
+            Err(SchnorrError::EquationMismatch)
+        );
+
+        let limb = F::from_le_bytes_mod_order(message);
+        let mut verify_transcript = PoseidonTranscript::<F>::new();
+        assert_eq!(
+            signature.verify(keypair.public_key, &mut verify_transcript, limb),
+            Err(SchnorrError::EquationMismatch)
+        );
+
+        let mut verify_transcript = PoseidonTranscript::<F>::new();
+        assert_eq!(
+            signature.verify_scalars(keypair.public_key, &mut verify_transcript, &[limb]),
+            Err(SchnorrError::EquationMismatch)
+        );
+    }
+
+    /// Tests that a signature never verifies under another encoding of the "same" message.
+    #[test]
+    fn test_message_encodings_unambiguous() {
+        type F = ark_bn254::Fr;
+        type G = ark_bn254::G1Projective;
+
+        let keypair = Keypair::<F, G>::generate();
+        let verify_all = |signature: &Signature<F, G>, scalar: F, scalars: &[F], bytes: &[u8]| {
+            let mut transcript = PoseidonTranscript::<F>::new();
+            let as_scalar = signature.verify(keypair.public_key, &mut transcript, scalar);
+            let mut transcript = PoseidonTranscript::<F>::new();
+            let as_scalars = signature.verify_scalars(keypair.public_key, &mut transcript, scalars);
+            let mut transcript = PoseidonTranscript::<F>::new();
+            let as_bytes = signature.verify_bytes(keypair.public_key, &mut transcript, bytes);
+            [as_scalar.is_ok(), as_scalars.is_ok(), as_bytes.is_ok()]
+        };
+
+        // Zero-padded, `[tag, len]` of an empty message used to equal a bare scalar.
+        for value in [0u64, 1, 2, 3] {
+            let scalar = F::from(value);
+            let mut transcript = PoseidonTranscript::<F>::new();
+            let signature = Signature::sign(&keypair, &mut transcript, scalar).unwrap();
+            assert_eq!(
+                verify_all(&signature, scalar, &[], b""),
+                [true, false, false]
+            );
+            assert_eq!(
+                verify_all(&signature, scalar, &[scalar], &[value as u8]),
+                [true, false, false]
+            );
+        }
+
+        let mut transcript = PoseidonTranscript::<F>::new();
+        let signature = Signature::sign_scalars(&keypair, &mut transcript, &[]).unwrap();
+        assert_eq!(
+            verify_all(&signature, F::from(1), &[], b""),
+            [false, true, false]
+        );
+
+        let mut transcript = PoseidonTranscript::<F>::new();
+        let signature = Signature::sign_bytes(&keypair, &mut transcript, b"").unwrap();
+        assert_eq!(
+            verify_all(&signature, F::from(2), &[], b""),
+            [false, false, true]
+        );
+    }
+
+    /// Tests signing and verifying slices of field elements.
+    #[test]
+    fn test_scalar_message_valid() {
+        type F = ark_bn254::Fr;
+        type G = ark_bn254::G1Projective;
+
+        let keypair = Keypair::<F, G>::generate();
+        let message = [F::from(1), F::from(2), F::from(3)];
+
+        let mut transcript = PoseidonTranscript::<F>::new();
+        let signature = Signature::sign_scalars(&keypair, &mut transcript, &message).unwrap();
+
+        let mut verify_transcript = PoseidonTranscript::<F>::new();
+        assert!(
+            signature
+                .verify_scalars(keypair.public_key, &mut verify_transcript, &message)
+                .is_ok()
+        );
+
+        let mut verify_transcript = PoseidonTranscript::<F>::new();
+        assert_eq!(
+            signature.verify_scalars(keypair.public_key, &mut verify_transcript, &message[..2]),
+            Err(SchnorrError::EquationMismatch)
+        );
+    }
+
+    /// Tests MuSig over a byte message.
+    #[test]
+    fn test_musig_byte_message_valid() {
+        type F = ark_bn254::Fr;
+        type G = ark_bn254::G1Projective;
+
+        let message = b"pay 10 to alice";
+
+        let k1 = Keypair::<F, G>::generate();
+        let (r1, R1) = MuSig::<F, G>::create_nonce();
+        let k2 = Keypair::<F, G>::generate();
+        let (r2, R2) = MuSig::<F, G>::create_nonce();
+
+        let pub_keys = [k1.public_key, k2.public_key];
+        let keyset_challenge = MuSig::keyset_challenge(&pub_keys).unwrap();
+        let agg_pub_key = MuSig::agg_pub_keys(&pub_keys, keyset_challenge).unwrap();
+        let agg_R = R1 + R2;
+
+        let s1 = MuSig::sign_bytes(k1, message, keyset_challenge, agg_pub_key, agg_R, r1).unwrap();
+        let s2 = MuSig::sign_bytes(k2, message, keyset_challenge, agg_pub_key, agg_R, r2).unwrap();
+        let agg_s = s1 + s2;
+        let musig = MuSig { agg_R, agg_s };
+
+        let mut verify_transcript = PoseidonTranscript::new();
+        assert!(
+            musig
+                .verify_bytes(agg_pub_key, &mut verify_transcript, message)
+                .is_ok()
+        );
+
+        let mut verify_transcript = PoseidonTranscript::new();
+        assert_eq!(
+            musig.verify_bytes(agg_pub_key, &mut verify_transcript, b"pay 99 to alice"),
+            Err(SchnorrError::EquationMismatch)
+        );
+    }
+
+    /// Tests that an identity public key is reported as such.
+    #[test]
+    fn test_verify_rejects_identity_public_key() {
+        type F = ark_bn254::Fr;
+        type G = ark_bn254::G1Projective;
+
+        let keypair = Keypair::<F, G>::generate();
+        let mut transcript = PoseidonTranscript::<F>::new();
+        let signature = Signature::sign(&keypair, &mut transcript, F::from(16)).unwrap();
+
+        let mut verify_transcript = PoseidonTranscript::<F>::new();
+        assert_eq!(
+            signature.verify(G::zero(), &mut verify_transcript, F::from(16)),
+            Err(SchnorrError::IdentityPublicKey)
+        );
+    }
+
+    /// Tests that an identity nonce commitment is reported as a malformed `R`.
+    #[test]
+    fn test_verify_rejects_identity_R() {
+        type F = ark_bn254::Fr;
+        type G = ark_bn254::G1Projective;
+
+        let keypair = Keypair::<F, G>::generate();
+        let signature = Signature::<F, G> {
+            R: G::zero(),
+            s: F::from(1),
+        };
+
+        let mut verify_transcript = PoseidonTranscript::<F>::new();
+        assert_eq!(
+            signature.verify(keypair.public_key, &mut verify_transcript, F::from(16)),
+            Err(SchnorrError::MalformedR)
+        );
+    }
+
+    /// Squeezes a challenge after absorbing a single point.
+    fn point_challenge(point: ark_bn254::G1Projective) -> Result<ark_bn254::Fr, SchnorrError> {
+        let mut transcript = PoseidonTranscript::new();
+        Transcript::<_, ark_bn254::G1Projective>::absorb_point(&mut transcript, point)?;
+        Ok(Transcript::<_, ark_bn254::G1Projective>::squeeze_challenge(
+            &mut transcript,
+        ))
+    }
+
+    /// Tests that distinct points, including a point and its negation, give distinct challenges.
+    #[test]
+    fn test_transcript_distinct_points() {
+        type G = ark_bn254::G1Projective;
+
+        let point = Keypair::<ark_bn254::Fr, G>::generate().public_key;
+        let points = [
+            point,
+            -point,
+            point.double(),
+            G::generator(),
+            -G::generator(),
+        ];
+
+        let challenges: Vec<_> = points
+            .iter()
+            .map(|point| point_challenge(*point).unwrap())
+            .collect();
+        for i in 0..challenges.len() {
+            for j in (i + 1)..challenges.len() {
+                assert_ne!(challenges[i], challenges[j], "points {i} and {j} collide");
+            }
+        }
+
+        // Projective representation does not matter.
+        let affine: ark_bn254::G1Affine = point.into();
+        assert_eq!(point_challenge(affine.into()), point_challenge(point));
+    }
+
+    /// Tests that the identity point is rejected explicitly.
+    #[test]
+    fn test_transcript_identity_point() {
+        type G = ark_bn254::G1Projective;
+
+        assert_eq!(point_challenge(G::zero()), Err(SchnorrError::IdentityPoint));
+    }
+
+    /// Tests that byte packing adapts the limb size to fields smaller than BN254.
+    #[test]
+    fn test_transcript_limbs_fit_small_fields() {
+        use ark_ff::{Fp64, MontBackend, fields::ark_ff_macros::MontConfig};
+
+        #[derive(MontConfig)]
+        #[modulus = "18446744069414584321"]
+        #[generator = "7"]
+        struct GoldilocksConfig;
+        type Goldilocks = Fp64<MontBackend<GoldilocksConfig, 1>>;
+
+        assert_eq!(bytes_per_limb::<ark_bn254::Fr>(), 31);
+        assert_eq!(bytes_per_limb::<Goldilocks>(), 7);
+
+        // With 8-byte limbs the modulus itself would pack to the same limb as zero.
+        let modulus_bytes = 18446744069414584321u64.to_le_bytes();
+        assert_ne!(
+            bytes_to_limbs::<Goldilocks>(&modulus_bytes),
+            bytes_to_limbs::<Goldilocks>(&[0u8; 8])
+        );
+        assert_eq!(
+            bytes_to_limbs::<Goldilocks>(&[0xff; 7]),
+            vec![Goldilocks::from((1u64 << 56) - 1)]
+        );
+    }
+
+    /// Tests signing with Poseidon2 selected as the transcript permutation.
+    #[test]
+    fn test_transcript_poseidon2_signature() {
+        type F = ark_bn254::Fr;
+        type G = ark_bn254::G1Projective;
+        type Poseidon2Transcript = PoseidonTranscript<F, 3, Poseidon2<F>>;
+
+        let message = F::from(16);
+        let keypair = Keypair::<F, G>::generate();
+
+        let mut transcript = Poseidon2Transcript::default();
+        let signature = Signature::sign(&keypair, &mut transcript, message).unwrap();
+
+        let mut verify_transcript = Poseidon2Transcript::default();
+        assert!(
+            signature
+                .verify(keypair.public_key, &mut verify_transcript, message)
+                .is_ok()
+        );
+        // The challenge depends on the permutation.
+        let mut verify_transcript = PoseidonTranscript::<F>::new();
+        assert_eq!(
+            signature.verify(keypair.public_key, &mut verify_transcript, message),
+            Err(SchnorrError::EquationMismatch)
+        );
+    }
+
+    /// Tests the commit, reveal and sign flow with three participants.
+    #[test]
+    fn test_musig_committed_valid() {
+        type F = ark_bn254::Fr;
+        type G = ark_bn254::G1Projective;
+
+        let message = F::from(16);
+        let keypairs: Vec<_> = (0..3).map(|_| Keypair::<F, G>::generate()).collect();
+        let pub_keys: Vec<_> = keypairs.iter().map(|k| k.public_key).collect();
+        let keyset_challenge = MuSig::keyset_challenge(&pub_keys).unwrap();
+        let agg_pub_key = MuSig::agg_pub_keys(&pub_keys, keyset_challenge).unwrap();
+
+        let nonces: Vec<_> = (0..3)
+            .map(|_| MuSig::<F, G>::create_committed_nonce().unwrap())
+            .collect();
+        let commitments: Vec<_> = nonces.iter().map(|(_, _, c)| *c).collect();
+        let revealed: Vec<_> = nonces.iter().map(|(_, R, _)| *R).collect();
+
+        let agg_s = keypairs
+            .iter()
+            .zip(&nonces)
+            .map(|(keypair, (r, _, _))| {
+                MuSig::sign_committed(
+                    keypair.clone(),
+                    message,
+                    keyset_challenge,
+                    agg_pub_key,
+                    &commitments,
+                    &revealed,
+                    *r,
+                )
+                .unwrap()
+            })
+            .sum();
+        let agg_R = MuSig::agg_revealed_nonces(&commitments, &revealed).unwrap();
+        let musig = MuSig { agg_R, agg_s };
+
+        let mut verify_transcript = PoseidonTranscript::new();
+        assert!(
+            musig
+                .verify(agg_pub_key, &mut verify_transcript, message)
+                .is_ok()
+        );
+    }
+
+    /// Tests that signing stops and names the participants whose nonces changed.
+    #[test]
+    fn test_musig_committed_reports_mismatch() {
+        type F = ark_bn254::Fr;
+        type G = ark_bn254::G1Projective;
+
+        let keypair = Keypair::<F, G>::generate();
+        let nonces: Vec<_> = (0..4)
+            .map(|_| MuSig::<F, G>::create_committed_nonce().unwrap())
+            .collect();
+        let commitments: Vec<_> = nonces.iter().map(|(_, _, c)| *c).collect();
+        let mut revealed: Vec<_> = nonces.iter().map(|(_, R, _)| *R).collect();
+
+        revealed[1] = MuSig::<F, G>::create_nonce().1;
+        revealed[3] = -revealed[3];
+
+        assert_eq!(
+            MuSig::agg_revealed_nonces(&commitments, &revealed),
+            Err(SchnorrError::NonceCommitmentMismatch(vec![1, 3]))
+        );
+        assert_eq!(
+            MuSig::sign_committed(
+                keypair.clone(),
+                F::from(16),
+                F::from(1),
+                keypair.public_key,
+                &commitments,
+                &revealed,
+                nonces[0].0,
+            ),
+            Err(SchnorrError::NonceCommitmentMismatch(vec![1, 3]))
+        );
+        assert_eq!(
+            MuSig::agg_revealed_nonces(&commitments, &revealed[..3]),
+            Err(SchnorrError::LengthMismatch {
+                expected: 4,
+                found: 3
+            })
+        );
+    }
+
+    /// Tests that tweaked keypairs are consistent and sign for the tweaked key.
+    #[test]
+    fn test_keypair_tweaks() {
+        type F = ark_bn254::Fr;
+        type G = ark_bn254::G1Projective;
+
+        let keypair = Keypair::<F, G>::generate();
+        let tweak = F::from(5);
+
+        let child = keypair.tweak(tweak, false).unwrap();
+        assert_eq!(
+            child.public_key,
+            keypair.public_key + G::generator() * tweak
+        );
+
+        let x_only_child = keypair.tweak(tweak, true).unwrap();
+        let negated = Keypair::<F, G>::from_private_key(-keypair.private_key);
+        assert_eq!(
+            x_only_child.public_key,
+            negated.tweak(tweak, true).unwrap().public_key
+        );
+
+        let hashed = keypair.tweak_by_hash(b"child", true).unwrap();
+        assert_eq!(
+            hashed.public_key,
+            negated.tweak_by_hash(b"child", true).unwrap().public_key
+        );
+        assert_ne!(
+            hashed.public_key,
+            keypair.tweak_by_hash(b"other", true).unwrap().public_key
+        );
+
+        let mut transcript = PoseidonTranscript::new();
+        let signature = Signature::sign(&hashed, &mut transcript, F::from(16)).unwrap();
+        let mut verify_transcript = PoseidonTranscript::new();
+        assert!(
+            signature
+                .verify(hashed.public_key, &mut verify_transcript, F::from(16))
+                .is_ok()
+        );
+
+        assert_eq!(
+            keypair.tweak(-keypair.private_key, false).err(),
+            Some(SchnorrError::IdentityPublicKey)
+        );
+    }
+
+    /// Tests three-round MuSig signing and partial verification for a tweaked aggregate key.
+    #[test]
+    fn test_musig_tweaked_aggregate_key() {
+        type F = ark_bn254::Fr;
+        type G = ark_bn254::G1Projective;
+
+        let message = F::from(16);
+        let keypairs: Vec<_> = (0..3).map(|_| Keypair::<F, G>::generate()).collect();
+        let pub_keys: Vec<_> = keypairs.iter().map(|k| k.public_key).collect();
+        let key_agg = KeyAggContext::new(&pub_keys)
+            .unwrap()
+            .tweak_by_hash(b"child", true)
+            .unwrap();
+
+        let nonces: Vec<_> = (0..3).map(|_| MuSig::<F, G>::create_nonce()).collect();
+        let revealed: Vec<_> = nonces.iter().map(|(_, R)| *R).collect();
+        let agg_R = revealed.iter().sum();
+        let partial_sigs: Vec<_> = keypairs
+            .iter()
+            .zip(&nonces)
+            .map(|(keypair, (r, _))| {
+                MuSig::sign_with_context(keypair, &key_agg, Message::Scalar(message), agg_R, *r)
+                    .unwrap()
+            })
+            .collect();
+
+        for ((partial_sig, R), pub_key) in partial_sigs.iter().zip(&revealed).zip(&pub_keys) {
+            assert!(
+                MuSig::verify_partial(
+                    *partial_sig,
+                    *R,
+                    *pub_key,
+                    &key_agg,
+                    agg_R,
+                    Message::Scalar(message),
+                )
+                .is_ok()
+            );
+        }
+        assert_eq!(
+            MuSig::verify_partial(
+                partial_sigs[0] + F::from(1),
+                revealed[0],
+                pub_keys[0],
+                &key_agg,
+                agg_R,
+                Message::Scalar(message),
+            ),
+            Err(SchnorrError::EquationMismatch)
+        );
+
+        let musig =
+            MuSig::aggregate(&partial_sigs, &revealed, &key_agg, Message::Scalar(message)).unwrap();
+        let mut verify_transcript = PoseidonTranscript::new();
+        assert!(
+            musig
+                .verify(key_agg.agg_pub_key(), &mut verify_transcript, message)
+                .is_ok()
+        );
+    }
+
+    /// Tests tweaked MuSig signing over a slice of field elements.
+    #[test]
+    fn test_musig_tweaked_scalars_message() {
+        type F = ark_bn254::Fr;
+        type G = ark_bn254::G1Projective;
+
+        let message = [F::from(1), F::from(2), F::from(3)];
+        let keypairs: Vec<_> = (0..2).map(|_| Keypair::<F, G>::generate()).collect();
+        let pub_keys: Vec<_> = keypairs.iter().map(|k| k.public_key).collect();
+        let key_agg = KeyAggContext::new(&pub_keys)
+            .unwrap()
+            .tweak_by_hash(b"child", true)
+            .unwrap();
+
+        let nonces: Vec<_> = (0..2).map(|_| MuSig::<F, G>::create_nonce()).collect();
+        let revealed: Vec<_> = nonces.iter().map(|(_, R)| *R).collect();
+        let agg_R = revealed.iter().sum();
+        let partial_sigs: Vec<_> = keypairs
+            .iter()
+            .zip(&nonces)
+            .map(|(keypair, (r, _))| {
+                MuSig::sign_with_context(keypair, &key_agg, Message::Scalars(&message), agg_R, *r)
+                    .unwrap()
+            })
+            .collect();
+
+        let musig = MuSig::aggregate(
+            &partial_sigs,
+            &revealed,
+            &key_agg,
+            Message::Scalars(&message),
+        )
+        .unwrap();
+        let mut verify_transcript = PoseidonTranscript::new();
+        assert!(
+            musig
+                .verify_scalars(key_agg.agg_pub_key(), &mut verify_transcript, &message)
+                .is_ok()
+        );
     }
 }
@@ -1,45 +1,152 @@
 //! Poseidon-based Fiat–Shamir transcript for Schnorr and MuSig signatures.
 //!
 //! Provides:
-//! - `Transcript` trait for absorbing points and scalars
+//! - `Transcript` trait for absorbing points, scalars, scalar slices and byte strings
 //! - `PoseidonTranscript` as a concrete implementation using a Poseidon sponge
 //! - Helper function `poseidon_transcript()` to create a boxed transcript
 //!
 //! Based on Fiat–Shamir transform adapted for ZK-friendly hash functions.
 
-use ark_ec::CurveGroup;
-use ark_ff::Field;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{Field, PrimeField};
+use ark_serialize::CanonicalSerialize;
 
-use crate::poseidon_hash::sponge::PoseidonSponge;
+use crate::{
+    error::SchnorrError,
+    poseidon_hash::{Permutation, Poseidon, sponge::PoseidonSponge},
+};
+
+/// Number of message bytes packed into one element of `F`.
+///
+/// A limb of `(MODULUS_BIT_SIZE - 1) / 8` bytes stays below the modulus of the
+/// base prime field, so the packing is injective for any field: 31 bytes for
+/// the BN254 scalar field. Panics for fields too small to hold a single byte.
+pub fn bytes_per_limb<F: Field>() -> usize {
+    let limb_bytes = (F::BasePrimeField::MODULUS_BIT_SIZE as usize - 1) / 8;
+    assert!(limb_bytes > 0, "field too small to pack bytes injectively");
+    limb_bytes
+}
+
+/// Domain tag absorbed before a length-prefixed slice of field elements.
+const SCALARS_TAG: u64 = 1;
+/// Domain tag absorbed before a length-prefixed byte string.
+const BYTES_TAG: u64 = 2;
+/// Domain tag absorbed before a single field element message.
+const SCALAR_TAG: u64 = 3;
+
+/// A message in one of the encodings accepted by the signing APIs.
+#[derive(Debug, Clone, Copy)]
+pub enum Message<'a, F: Field> {
+    /// A single field element, absorbed after its own domain tag.
+    Scalar(F),
+    /// A slice of field elements, absorbed with `Transcript::absorb_scalars`.
+    Scalars(&'a [F]),
+    /// An arbitrary byte string, absorbed with `Transcript::absorb_bytes`.
+    Bytes(&'a [u8]),
+}
 
 /// Fiat–Shamir transcript trait with point and scalar absorption.
 pub trait Transcript<F: Field, G: CurveGroup> {
-    fn absorb_point(&mut self, point: G);
+    /// Absorbs a curve point, failing if the point has no transcript encoding.
+    fn absorb_point(&mut self, point: G) -> Result<(), SchnorrError>;
     fn absorb_scalar(&mut self, scalar: F);
     fn squeeze_challenge(&mut self) -> F;
+
+    /// Absorbs a slice of scalars as a tag, its length, then every element.
+    fn absorb_scalars(&mut self, scalars: &[F]) {
+        self.absorb_scalar(F::from(SCALARS_TAG));
+        self.absorb_scalar(F::from(scalars.len() as u64));
+        for scalar in scalars {
+            self.absorb_scalar(*scalar);
+        }
+    }
+
+    /// Absorbs a byte string as a tag, its length, then little-endian limbs of
+    /// `bytes_per_limb` bytes.
+    fn absorb_bytes(&mut self, bytes: &[u8]) {
+        self.absorb_scalar(F::from(BYTES_TAG));
+        self.absorb_scalar(F::from(bytes.len() as u64));
+        for limb in bytes_to_limbs::<F>(bytes) {
+            self.absorb_scalar(limb);
+        }
+    }
+
+    /// Absorbs a message according to its encoding.
+    ///
+    /// Every encoding starts with its own tag and has a length fixed by its
+    /// header, so no two messages of any encodings absorb the same elements,
+    /// even after the sponge zero-pads the last chunk.
+    fn absorb_message(&mut self, message: Message<'_, F>) {
+        match message {
+            Message::Scalar(scalar) => {
+                self.absorb_scalar(F::from(SCALAR_TAG));
+                self.absorb_scalar(scalar);
+            }
+            Message::Scalars(scalars) => self.absorb_scalars(scalars),
+            Message::Bytes(bytes) => self.absorb_bytes(bytes),
+        }
+    }
+}
+
+/// Packs bytes into field elements of `bytes_per_limb` little-endian bytes each.
+pub(crate) fn bytes_to_limbs<F: Field>(bytes: &[u8]) -> Vec<F> {
+    bytes
+        .chunks(bytes_per_limb::<F>())
+        .map(|chunk| F::from_base_prime_field(F::BasePrimeField::from_le_bytes_mod_order(chunk)))
+        .collect()
 }
 
 /// Poseidon-based implementation of the `Transcript` trait.
+///
+/// `P` selects the sponge permutation, by default Poseidon with the BN254
+/// width-5 parameters. Other permutations, such as `Poseidon2`, are created
+/// with `Default::default`.
 #[derive(Debug, Clone)]
-pub struct PoseidonTranscript<F: Field> {
-    sponge: PoseidonSponge<F>,
+pub struct PoseidonTranscript<
+    F: Field,
+    const WIDTH: usize = 5,
+    P: Permutation<F, WIDTH> = Poseidon<F>,
+> {
+    sponge: PoseidonSponge<F, WIDTH, P>,
 }
 
 impl<F: Field> PoseidonTranscript<F> {
     /// Creates a new Poseidon-based transcript.
     pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<F: Field, const WIDTH: usize, P: Permutation<F, WIDTH>> Default
+    for PoseidonTranscript<F, WIDTH, P>
+{
+    fn default() -> Self {
         PoseidonTranscript {
             sponge: PoseidonSponge::new(),
         }
     }
 }
 
-impl<F: Field, G: CurveGroup> Transcript<F, G> for PoseidonTranscript<F> {
-    fn absorb_point(&mut self, point: G) {
-        let mut compressed_bytes = Vec::new();
-        point.serialize_compressed(&mut compressed_bytes).unwrap();
-        let field_element = F::from_random_bytes(&compressed_bytes).unwrap();
-        self.sponge.update(&[field_element]);
+impl<F: Field, G: CurveGroup, const WIDTH: usize, P: Permutation<F, WIDTH>> Transcript<F, G>
+    for PoseidonTranscript<F, WIDTH, P>
+{
+    /// Absorbs the affine coordinates `x || y` packed into `bytes_per_limb` byte limbs.
+    ///
+    /// The encoding is injective on non-identity points, and its length only
+    /// depends on the curve. The identity has no affine coordinates and is
+    /// rejected with `SchnorrError::IdentityPoint`.
+    fn absorb_point(&mut self, point: G) -> Result<(), SchnorrError> {
+        let (x, y) = point
+            .into_affine()
+            .xy()
+            .ok_or(SchnorrError::IdentityPoint)?;
+
+        let mut coordinate_bytes = Vec::with_capacity(x.compressed_size() + y.compressed_size());
+        x.serialize_compressed(&mut coordinate_bytes)
+            .and_then(|_| y.serialize_compressed(&mut coordinate_bytes))
+            .expect("serializing into a Vec cannot fail");
+        self.sponge.update(&bytes_to_limbs(&coordinate_bytes));
+        Ok(())
     }
 
     fn absorb_scalar(&mut self, scalar: F) {
@@ -0,0 +1,36 @@
+//! Helpers shared by keypair and aggregate-key tweaking.
+//!
+//! A point has "even y" when its affine `y` is the smaller of `y` and `-y`.
+//! X-only tweaks first replace a key by its even-y representative, so a
+//! tweaked key only depends on the x-coordinate of the original, as in Taproot.
+
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::Field;
+
+use crate::{error::SchnorrError, transcript::poseidon_transcript};
+
+/// Domain separator absorbed before hashing a tweak ("tweak" in ASCII).
+const TWEAK_TAG: u64 = 0x747765616b;
+
+/// Returns whether `point` has even y; the identity counts as even.
+pub(crate) fn has_even_y<G: CurveGroup>(point: G) -> bool {
+    point.into_affine().xy().is_none_or(|(_, y)| y <= -y)
+}
+
+/// Returns whichever of `point` and `-point` has even y.
+pub(crate) fn even_y<G: CurveGroup>(point: G) -> G {
+    if has_even_y(point) { point } else { -point }
+}
+
+/// Derives a tweak as the Poseidon hash of the key being tweaked and `data`.
+pub(crate) fn hash_tweak<F: Field, G: CurveGroup<ScalarField = F>>(
+    public_key: G,
+    data: &[u8],
+) -> Result<F, SchnorrError> {
+    let mut tweak_transcript = poseidon_transcript::<F, G>();
+    tweak_transcript.absorb_scalar(F::from(TWEAK_TAG));
+    tweak_transcript.absorb_point(public_key)?;
+    tweak_transcript.absorb_bytes(data);
+
+    Ok(tweak_transcript.squeeze_challenge())
+}